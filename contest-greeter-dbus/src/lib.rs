@@ -1,10 +1,41 @@
-use zbus::interface;
+use std::{future::Future, pin::Pin};
+
+use zbus::{Connection, fdo, interface, message::Header, names::BusName};
+
+/// Identity of the process calling a method, as reported by the bus.
+#[derive(Clone, Debug)]
+pub struct Caller {
+    pub sender: String,
+    pub uid: Option<u32>,
+    pub gids: Option<Vec<u32>>,
+    pub pid: Option<u32>,
+}
 
 pub trait GreeterServiceBackend: Send + Sync {
+    /// Decides whether `caller` may call `method`; checked before every method call.
+    fn authorize(
+        &self,
+        method: &'static str,
+        caller: Caller,
+        connection: Connection,
+    ) -> Pin<Box<dyn Future<Output = fdo::Result<()>> + Send>>;
+
     fn set_wallpaper_source(&self, url: String);
+    fn set_wallpaper_playlist(&self, sources: Vec<String>, interval: u32);
     fn set_countdown_endtime(&self, end_time: i64) -> zbus::fdo::Result<()>;
     fn disable_countdown(&self);
     fn login(&self);
+    fn show_message(
+        &self,
+        title: String,
+        body: String,
+        severity: String,
+        timeout: u32,
+    ) -> zbus::fdo::Result<()>;
+    fn clear_message(&self);
+    fn set_log_level(&self, level: String) -> zbus::fdo::Result<()>;
+    fn show_diagnostics(&self, visible: bool);
+    fn wallpaper_status(&self) -> Pin<Box<dyn Future<Output = String> + Send>>;
 }
 
 pub struct GreeterService<B: 'static> {
@@ -17,6 +48,48 @@ impl<B> GreeterService<B> {
     }
 }
 
+/// Object path the service is served at.
+pub const OBJECT_PATH: &str = "/nl/luukblankenstijn/ContestGreeterService";
+
+impl<B: GreeterServiceBackend> GreeterService<B> {
+    /// Emits `PropertiesChanged` for `WallpaperStatus` from the service served on `connection`.
+    pub async fn notify_wallpaper_status(connection: &Connection) -> zbus::Result<()> {
+        let iface = connection
+            .object_server()
+            .interface::<_, Self>(OBJECT_PATH)
+            .await?;
+        iface
+            .get()
+            .await
+            .wallpaper_status_changed(iface.signal_emitter())
+            .await
+    }
+
+    async fn check(
+        &self,
+        method: &'static str,
+        header: &Header<'_>,
+        connection: &Connection,
+    ) -> fdo::Result<()> {
+        let sender = header
+            .sender()
+            .ok_or(fdo::Error::AccessDenied("unknown sender".into()))?;
+        let credentials = fdo::DBusProxy::new(connection)
+            .await?
+            .get_connection_credentials(BusName::from(sender.to_owned()))
+            .await?;
+        let caller = Caller {
+            sender: sender.to_string(),
+            uid: credentials.unix_user_id(),
+            gids: credentials.unix_group_ids().cloned(),
+            pid: credentials.process_id(),
+        };
+        self.backend
+            .authorize(method, caller, connection.clone())
+            .await
+    }
+}
+
 /// D-Bus service definition and generated proxy.
 #[interface(
     name = "nl.luukblankenstijn.ContestGreeterService",
@@ -29,24 +102,122 @@ impl<B> GreeterService<B> {
 impl<B: GreeterServiceBackend> GreeterService<B> {
     /// Sets the source of the wallpaper to use. Can be an http url
     /// (if the machine has internet) or a local filepath.
-    async fn set_wallpaper_source(&self, url: String) {
+    async fn set_wallpaper_source(
+        &self,
+        url: String,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> fdo::Result<()> {
+        self.check("SetWallpaperSource", &header, connection)
+            .await?;
         self.backend.set_wallpaper_source(url);
+        Ok(())
+    }
+
+    /// Rotates the wallpaper through the given sources (http urls, file paths or directories),
+    /// switching every `interval` seconds.
+    async fn set_wallpaper_playlist(
+        &self,
+        sources: Vec<String>,
+        interval: u32,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> fdo::Result<()> {
+        self.check("SetWallpaperPlaylist", &header, connection)
+            .await?;
+        self.backend.set_wallpaper_playlist(sources, interval);
+        Ok(())
     }
 
     /// Sets the time the countdown (if enabled) will end, and the session will be started.
     /// The i64 argument is the miliseconds since epoch.
-    async fn set_countdown_endtime(&self, end_time: i64) -> zbus::fdo::Result<()> {
+    async fn set_countdown_endtime(
+        &self,
+        end_time: i64,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> fdo::Result<()> {
+        self.check("SetCountdownEndtime", &header, connection)
+            .await?;
         self.backend.set_countdown_endtime(end_time)
     }
 
     /// Disable the countdown and the subsequent login by removing the endtime.
-    async fn disable_countdown(&self) {
+    async fn disable_countdown(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> fdo::Result<()> {
+        self.check("DisableCountdown", &header, connection).await?;
         self.backend.disable_countdown();
+        Ok(())
     }
 
     /// Unlocks the machine and starts the default session.
     /// This only works when a username and password have been configured for the greeter.
-    async fn login(&self) {
+    async fn login(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> fdo::Result<()> {
+        self.check("Login", &header, connection).await?;
         self.backend.login();
+        Ok(())
+    }
+
+    /// Shows a message on top of the greeter. Severity is one of `info`, `warning` or `critical`.
+    /// The message is hidden after `timeout` seconds, or stays until cleared when it is 0.
+    async fn show_message(
+        &self,
+        title: String,
+        body: String,
+        severity: String,
+        timeout: u32,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> fdo::Result<()> {
+        self.check("ShowMessage", &header, connection).await?;
+        self.backend.show_message(title, body, severity, timeout)
+    }
+
+    /// Hides the message shown with `ShowMessage`, if any.
+    async fn clear_message(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> fdo::Result<()> {
+        self.check("ClearMessage", &header, connection).await?;
+        self.backend.clear_message();
+        Ok(())
+    }
+
+    /// Changes the log level at runtime, using env_logger syntax (e.g. `debug` or
+    /// `info,zbus=warn`).
+    async fn set_log_level(
+        &self,
+        level: String,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> fdo::Result<()> {
+        self.check("SetLogLevel", &header, connection).await?;
+        self.backend.set_log_level(level)
+    }
+
+    /// Shows or hides the diagnostics panel for floor staff.
+    async fn show_diagnostics(
+        &self,
+        visible: bool,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+    ) -> fdo::Result<()> {
+        self.check("ShowDiagnostics", &header, connection).await?;
+        self.backend.show_diagnostics(visible);
+        Ok(())
+    }
+
+    /// Status of the current wallpaper, e.g. `loaded: <source>` or `invalid: <source>: <reason>`.
+    #[zbus(property)]
+    async fn wallpaper_status(&self) -> String {
+        self.backend.wallpaper_status().await
     }
 }
@@ -0,0 +1,82 @@
+# Text shown to contestants, see https://projectfluent.org for the syntax.
+# English is the fallback for languages and messages without a translation.
+
+## Wallpaper
+
+wallpaper-missing = No Wallpaper
+wallpaper-invalid = Invalid Wallpaper
+
+## Countdown, clock and contest phase
+
+countdown-starting = Starting...
+clock-starts-at = starts at { $time }
+clock-duration = duration { $duration }
+phase-running = Contest running
+phase-frozen = Contest running, scoreboard frozen
+phase-ended = Contest over
+phase-wait-title = Waiting for the contest server
+phase-wait = Logging in anyway in { $seconds } seconds.
+
+## Identity panel
+
+identity-seat = Seat: { $seat }
+identity-host = Host: { $host }
+identity-ip = IP: { $addresses }
+
+## Login panel
+
+login-username = username
+login-password = password
+login-password-layout = password ({ $layout })
+login-keyboard = Keyboard
+login-failed = Authentication failed
+login-locked-out =
+    { $seconds ->
+        [one] Too many failed attempts, try again in { $seconds } second
+       *[other] Too many failed attempts, try again in { $seconds } seconds
+    }
+login-caps-lock = Caps Lock is on
+login-num-lock = Num Lock is off
+login-show-password = Show password
+login-hide-password = Hide password
+login-button = Log in
+reboot-failed = Reboot failed: { $error }
+reboot-confirm-title = Reboot this machine?
+reboot-confirm = Press the reboot keys again within { $seconds } seconds to reboot.
+config-reloaded-title = Configuration reloaded
+config-reloaded = All changes are applied.
+config-reloaded-restart = Restart the greeter to apply the changes to: { $settings }
+config-reload-failed-title = Configuration reload failed
+
+## Staff attention screen
+
+staff-attention-title = Please call a staff member
+staff-attention-autologin = Automatic login was attempted { $attempts } times in the last { $minutes } minutes.
+
+## Announcements
+
+clarification-title = Announcement
+
+## Accessibility
+
+a11y-high-contrast-on = High contrast on
+a11y-high-contrast-off = High contrast off
+a11y-large-text-on = Large text on
+a11y-large-text-off = Large text off
+countdown-name = Time until the contest starts
+countdown-announce-minutes =
+    { $minutes ->
+        [one] The contest starts in { $minutes } minute
+       *[other] The contest starts in { $minutes } minutes
+    }
+countdown-announce-seconds =
+    { $seconds ->
+        [one] The contest starts in { $seconds } second
+       *[other] The contest starts in { $seconds } seconds
+    }
+countdown-announce-start = The contest is starting
+login-name = Log in
+login-close = Close
+clock-name = Clock
+identity-name = Seat identity
+message-name = Announcement
@@ -0,0 +1,79 @@
+## Wallpaper
+
+wallpaper-missing = Sin fondo de pantalla
+wallpaper-invalid = Fondo de pantalla no válido
+
+## Countdown, clock and contest phase
+
+countdown-starting = Comenzando...
+clock-starts-at = comienza a las { $time }
+clock-duration = duración { $duration }
+phase-running = Competencia en curso
+phase-frozen = Competencia en curso, marcador congelado
+phase-ended = Competencia finalizada
+phase-wait-title = Esperando al servidor del concurso
+phase-wait = Se iniciará sesión de todos modos en { $seconds } segundos.
+
+## Identity panel
+
+identity-seat = Puesto: { $seat }
+identity-host = Máquina: { $host }
+identity-ip = IP: { $addresses }
+
+## Login panel
+
+login-username = usuario
+login-password = contraseña
+login-password-layout = contraseña ({ $layout })
+login-keyboard = Teclado
+login-failed = Error de autenticación
+login-locked-out =
+    { $seconds ->
+        [one] Demasiados intentos fallidos, inténtelo de nuevo en { $seconds } segundo
+       *[other] Demasiados intentos fallidos, inténtelo de nuevo en { $seconds } segundos
+    }
+login-caps-lock = Bloq Mayús está activado
+login-num-lock = Bloq Num está desactivado
+login-show-password = Mostrar contraseña
+login-hide-password = Ocultar contraseña
+login-button = Iniciar sesión
+reboot-failed = Error al reiniciar: { $error }
+reboot-confirm-title = ¿Reiniciar esta máquina?
+reboot-confirm = Pulse de nuevo las teclas de reinicio antes de { $seconds } segundos para reiniciar.
+config-reloaded-title = Configuración recargada
+config-reloaded = Se aplicaron todos los cambios.
+config-reloaded-restart = Reinicie el greeter para aplicar los cambios en: { $settings }
+config-reload-failed-title = No se pudo recargar la configuración
+
+## Staff attention screen
+
+staff-attention-title = Por favor, llame a un miembro de la organización
+staff-attention-autologin = Se intentó iniciar sesión automáticamente { $attempts } veces en los últimos { $minutes } minutos.
+
+## Announcements
+
+clarification-title = Anuncio
+
+## Accessibility
+
+a11y-high-contrast-on = Alto contraste activado
+a11y-high-contrast-off = Alto contraste desactivado
+a11y-large-text-on = Texto grande activado
+a11y-large-text-off = Texto grande desactivado
+countdown-name = Tiempo hasta el inicio de la competencia
+countdown-announce-minutes =
+    { $minutes ->
+        [one] La competencia comienza en { $minutes } minuto
+       *[other] La competencia comienza en { $minutes } minutos
+    }
+countdown-announce-seconds =
+    { $seconds ->
+        [one] La competencia comienza en { $seconds } segundo
+       *[other] La competencia comienza en { $seconds } segundos
+    }
+countdown-announce-start = La competencia está comenzando
+login-name = Iniciar sesión
+login-close = Cerrar
+clock-name = Reloj
+identity-name = Identificación del puesto
+message-name = Anuncio
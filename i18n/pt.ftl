@@ -0,0 +1,79 @@
+## Wallpaper
+
+wallpaper-missing = Sem papel de parede
+wallpaper-invalid = Papel de parede inválido
+
+## Countdown, clock and contest phase
+
+countdown-starting = Iniciando...
+clock-starts-at = começa às { $time }
+clock-duration = duração { $duration }
+phase-running = Competição em andamento
+phase-frozen = Competição em andamento, placar congelado
+phase-ended = Competição encerrada
+phase-wait-title = Aguardando o servidor da competição
+phase-wait = O login será feito mesmo assim em { $seconds } segundos.
+
+## Identity panel
+
+identity-seat = Posição: { $seat }
+identity-host = Máquina: { $host }
+identity-ip = IP: { $addresses }
+
+## Login panel
+
+login-username = usuário
+login-password = senha
+login-password-layout = senha ({ $layout })
+login-keyboard = Teclado
+login-failed = Falha na autenticação
+login-locked-out =
+    { $seconds ->
+        [one] Muitas tentativas sem sucesso, tente novamente em { $seconds } segundo
+       *[other] Muitas tentativas sem sucesso, tente novamente em { $seconds } segundos
+    }
+login-caps-lock = Caps Lock está ativado
+login-num-lock = Num Lock está desativado
+login-show-password = Mostrar senha
+login-hide-password = Ocultar senha
+login-button = Entrar
+reboot-failed = Falha ao reiniciar: { $error }
+reboot-confirm-title = Reiniciar esta máquina?
+reboot-confirm = Pressione as teclas de reinício novamente em até { $seconds } segundos para reiniciar.
+config-reloaded-title = Configuração recarregada
+config-reloaded = Todas as alterações foram aplicadas.
+config-reloaded-restart = Reinicie o greeter para aplicar as alterações em: { $settings }
+config-reload-failed-title = Falha ao recarregar a configuração
+
+## Staff attention screen
+
+staff-attention-title = Por favor, chame um membro da organização
+staff-attention-autologin = O login automático foi tentado { $attempts } vezes nos últimos { $minutes } minutos.
+
+## Announcements
+
+clarification-title = Aviso
+
+## Accessibility
+
+a11y-high-contrast-on = Alto contraste ativado
+a11y-high-contrast-off = Alto contraste desativado
+a11y-large-text-on = Texto grande ativado
+a11y-large-text-off = Texto grande desativado
+countdown-name = Tempo até o início da competição
+countdown-announce-minutes =
+    { $minutes ->
+        [one] A competição começa em { $minutes } minuto
+       *[other] A competição começa em { $minutes } minutos
+    }
+countdown-announce-seconds =
+    { $seconds ->
+        [one] A competição começa em { $seconds } segundo
+       *[other] A competição começa em { $seconds } segundos
+    }
+countdown-announce-start = A competição está começando
+login-name = Entrar
+login-close = Fechar
+clock-name = Relógio
+identity-name = Identificação da posição
+message-name = Aviso
@@ -0,0 +1,67 @@
+## Wallpaper
+
+wallpaper-missing = 无壁纸
+wallpaper-invalid = 壁纸无效
+
+## Countdown, clock and contest phase
+
+countdown-starting = 即将开始...
+clock-starts-at = { $time } 开始
+clock-duration = 时长 { $duration }
+phase-running = 比赛进行中
+phase-frozen = 比赛进行中，榜单已封榜
+phase-ended = 比赛已结束
+phase-wait-title = 正在等待比赛服务器
+phase-wait = 将在 { $seconds } 秒后直接登录。
+
+## Identity panel
+
+identity-seat = 座位：{ $seat }
+identity-host = 主机：{ $host }
+identity-ip = IP：{ $addresses }
+
+## Login panel
+
+login-username = 用户名
+login-password = 密码
+login-password-layout = 密码（{ $layout }）
+login-keyboard = 键盘
+login-failed = 认证失败
+login-locked-out = 失败次数过多，请在 { $seconds } 秒后重试
+login-caps-lock = 大写锁定已开启
+login-num-lock = 数字锁定已关闭
+login-show-password = 显示密码
+login-hide-password = 隐藏密码
+login-button = 登录
+reboot-failed = 重启失败：{ $error }
+reboot-confirm-title = 重启这台机器？
+reboot-confirm = 请在 { $seconds } 秒内再次按下重启快捷键以重启。
+config-reloaded-title = 配置已重新加载
+config-reloaded = 所有更改均已生效。
+config-reloaded-restart = 以下设置的更改需要重启登录界面后生效：{ $settings }
+config-reload-failed-title = 重新加载配置失败
+
+## Staff attention screen
+
+staff-attention-title = 请联系工作人员
+staff-attention-autologin = 过去 { $minutes } 分钟内已尝试自动登录 { $attempts } 次。
+
+## Announcements
+
+clarification-title = 公告
+
+## Accessibility
+
+a11y-high-contrast-on = 高对比度已开启
+a11y-high-contrast-off = 高对比度已关闭
+a11y-large-text-on = 大字体已开启
+a11y-large-text-off = 大字体已关闭
+countdown-name = 距比赛开始的时间
+countdown-announce-minutes = 比赛将在 { $minutes } 分钟后开始
+countdown-announce-seconds = 比赛将在 { $seconds } 秒后开始
+countdown-announce-start = 比赛即将开始
+login-name = 登录
+login-close = 关闭
+clock-name = 时钟
+identity-name = 座位信息
+message-name = 公告
@@ -44,6 +44,11 @@ cat >/etc/dbus-1/system.d/nl.luukblankenstijn.ContestGreeterService.conf <<'EOF'
 </busconfig>
 EOF
 
+install -D -m 0644 "$tmp_dir/nl.luukblankenstijn.ContestGreeterService.policy" \
+    /usr/share/polkit-1/actions/nl.luukblankenstijn.ContestGreeterService.policy
+
+install -d -o lightdm -g lightdm /var/lib/lightdm/contest-greeter
+
 install -d /usr/share/xgreeters
 cat >/usr/share/xgreeters/lightdm-contest-greeter.desktop <<'EOF'
 [Desktop Entry]
@@ -4,6 +4,8 @@ use log::{debug, error, info};
 use tokio::sync::mpsc;
 use types::{CoreName, ServiceChannel, SystemMsg};
 
+use crate::metrics::BUS_MESSAGES;
+
 pub async fn start_bus(mut rx: mpsc::Receiver<SystemMsg>) {
     let mut registry: HashMap<CoreName, Box<dyn ServiceChannel>> = HashMap::new();
 
@@ -18,11 +20,14 @@ pub async fn start_bus(mut rx: mpsc::Receiver<SystemMsg>) {
                 registry.insert(name, channel);
             }
             SystemMsg::Route { to, payload } => {
-                if let Some(service) = registry.get(&to) {
-                    service.send_any(payload);
+                let delivered = if let Some(service) = registry.get(&to) {
+                    service.send_any(payload)
                 } else {
-                    debug!("[Bus] service {:?} not found for incoming message", to)
-                }
+                    debug!("[Bus] service {:?} not found for incoming message", to);
+                    false
+                };
+                let result = if delivered { "routed" } else { "dropped" };
+                BUS_MESSAGES.inc(&[("core", &format!("{to:?}")), ("result", result)]);
             }
         }
     }
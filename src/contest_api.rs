@@ -1,11 +1,19 @@
-use anyhow::{Context, Result};
-use chrono::{DateTime, FixedOffset, Local};
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, FixedOffset, Local, TimeDelta};
 use log::{debug, info};
-use reqwest::Client;
+use reqwest::{Client, header};
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
-use tokio::time::{Duration, sleep};
-use types::{CoreName, GreeterMessage, SystemSender, UiMessage};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use tokio::time::{Duration, Instant, sleep};
+
+use crate::{
+    host, i18n,
+    metrics::{API_POLL_LAST_SECONDS, API_POLL_SECONDS, API_POLLS, CLOCK_OFFSET},
+};
+use types::{
+    Announcement, ContestInfo, ContestPhase, CoreName, GreeterMessage, LoginSource, Severity,
+    SourceHealth, StatusMessage, SystemSender, UiMessage,
+};
 
 #[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
 pub struct ApiPollerConfig {
@@ -13,8 +21,23 @@ pub struct ApiPollerConfig {
     #[serde(default = "default_interval")]
     interval: i64,
 
-    /// Contest API URL returning a JSON object with `start_time` (RFC3339).
+    /// Contest API URL returning a JSON object with `start_time` (RFC3339) and optionally
+    /// `duration` and `scoreboard_freeze_duration` (CCS relative time, e.g. `5:00:00.000`).
     url: Option<String>,
+
+    /// CCS clarifications endpoint (e.g. `.../contests/<id>/clarifications`). The latest public
+    /// announcement from the jury is shown on the greeter.
+    clarifications_url: Option<String>,
+
+    /// CCS teams endpoint (e.g. `.../contests/<id>/teams`). The team whose `hostname` or `ip`
+    /// matches this machine is shown in the identity panel.
+    teams_url: Option<String>,
+}
+
+impl ApiPollerConfig {
+    pub fn url(&self) -> Option<&str> {
+        self.url.as_deref()
+    }
 }
 
 impl Default for ApiPollerConfig {
@@ -22,6 +45,8 @@ impl Default for ApiPollerConfig {
         Self {
             interval: default_interval(),
             url: None,
+            clarifications_url: None,
+            teams_url: None,
         }
     }
 }
@@ -31,31 +56,75 @@ fn default_interval() -> i64 {
 }
 
 pub async fn run_api_poller(bus: impl SystemSender, config: ApiPollerConfig) {
-    let Some(url) = config.url else {
+    let client = Client::new();
+    tokio::join!(
+        poll_contest(&bus, &client, &config),
+        poll_clarifications(&bus, &client, &config),
+        poll_team(&bus, &client, &config)
+    );
+}
+
+async fn poll_contest(bus: &impl SystemSender, client: &Client, config: &ApiPollerConfig) {
+    let Some(url) = &config.url else {
         info!("[Contest-Api] contest url not set, not running api poller");
         return;
     };
 
-    // keep the bus alive for future message routing even though it is unused for now
     let poll_interval = Duration::from_secs(config.interval.max(0) as u64);
-
-    let client = Client::new();
+    let mut last_phase = None;
+    let mut last_info = None;
 
     loop {
-        match fetch_start_time(&client, &url).await {
-            Ok(start_time) => {
+        let result = fetch_contest(client, url).await;
+        let health = match &result {
+            Ok(_) => SourceHealth::Healthy,
+            Err(e) => SourceHealth::Failing(format!("{e:#}")),
+        };
+        bus.send_to(CoreName::Status, StatusMessage::SetContestApi(health));
+
+        match result {
+            Ok(contest) => {
+                let info = contest.info();
+                if last_info.as_ref() != Some(&info) {
+                    bus.send_to(CoreName::UI, UiMessage::SetContestInfo(info.clone()));
+                    last_info = Some(info);
+                }
+
                 let now = Local::now();
-                if start_time < now {
-                    info!("[Contest-Api] contest started at {start_time} — triggering login");
-                    bus.send_to(CoreName::Greeter, GreeterMessage::Login());
-                } else {
-                    bus.send_to(
-                        CoreName::UI,
-                        UiMessage::SetCountdownEndtime {
-                            end_time: Some(start_time),
-                        },
-                    );
-                    debug!("[Contest-Api] contest not started yet (starts at {start_time})");
+                let phase = contest.phase_at(now);
+                if last_phase != Some(phase) {
+                    info!("[Contest-Api] contest phase is now {phase:?}");
+                    bus.send_to(CoreName::UI, UiMessage::SetContestPhase(phase));
+                    bus.send_to(CoreName::Greeter, GreeterMessage::SetContestPhase(phase));
+                    last_phase = Some(phase);
+                }
+
+                match phase {
+                    ContestPhase::BeforeStart => {
+                        bus.send_to(
+                            CoreName::UI,
+                            UiMessage::SetCountdownEndtime {
+                                end_time: Some(contest.start_time),
+                            },
+                        );
+                        debug!(
+                            "[Contest-Api] contest not started yet (starts at {})",
+                            contest.start_time
+                        );
+                    }
+                    ContestPhase::Running | ContestPhase::Frozen => {
+                        info!(
+                            "[Contest-Api] contest started at {} — triggering login",
+                            contest.start_time
+                        );
+                        bus.send_to(
+                            CoreName::Greeter,
+                            GreeterMessage::Login(LoginSource::ContestApi),
+                        );
+                    }
+                    ContestPhase::Ended => {
+                        debug!("[Contest-Api] contest has ended, not triggering login");
+                    }
                 }
             }
             Err(e) => debug!("[Contest-Api] failed to poll contest API ({url}): {:#}", e),
@@ -65,21 +134,255 @@ pub async fn run_api_poller(bus: impl SystemSender, config: ApiPollerConfig) {
     }
 }
 
-#[derive(Deserialize)]
-struct ContestApiResponse {
-    start_time: DateTime<FixedOffset>,
+async fn poll_clarifications(bus: &impl SystemSender, client: &Client, config: &ApiPollerConfig) {
+    let Some(url) = &config.clarifications_url else {
+        debug!("[Contest-Api] clarifications url not set, not polling announcements");
+        return;
+    };
+
+    let poll_interval = Duration::from_secs(config.interval.max(0) as u64);
+    let mut last_id = None;
+
+    loop {
+        match fetch_latest_announcement(client, url).await {
+            Ok(Some(clarification)) if last_id.as_ref() != Some(&clarification.id) => {
+                info!("[Contest-Api] new announcement {}", clarification.id);
+                bus.send_to(
+                    CoreName::UI,
+                    UiMessage::ShowMessage(Announcement {
+                        title: i18n::tr("clarification-title"),
+                        body: clarification.text,
+                        severity: Severity::Info,
+                        expires_at: None,
+                    }),
+                );
+                last_id = Some(clarification.id);
+            }
+            Ok(_) => {}
+            Err(e) => debug!(
+                "[Contest-Api] failed to poll clarifications ({url}): {:#}",
+                e
+            ),
+        }
+
+        sleep(poll_interval).await;
+    }
 }
 
-async fn fetch_start_time(client: &Client, url: &str) -> Result<DateTime<Local>> {
+/// Sends a GET request to the contest API and decodes the JSON payload, recording its latency,
+/// result and the clock offset of the server. A payload that can't be decoded counts as failure.
+async fn request<T: DeserializeOwned>(
+    client: &Client,
+    url: &str,
+    endpoint: &'static str,
+) -> Result<T> {
+    let started = Instant::now();
+    let sent_at = Local::now();
     let response = client
         .get(url)
         .send()
         .await
-        .context(format!("sending request to {url}"))?
-        .error_for_status()
-        .context("server returned error")?;
+        .context(format!("sending request to {url}"))
+        .and_then(|r| r.error_for_status().context("server returned error"));
+
+    // the response headers arrive before the body, so this is the time the server answered
+    let answered = started.elapsed();
+    let server_time = response.as_ref().ok().and_then(|response| {
+        response
+            .headers()
+            .get(header::DATE)
+            .and_then(|date| date.to_str().ok())
+            .and_then(|date| DateTime::parse_from_rfc2822(date).ok())
+    });
+    let result = match response {
+        Ok(response) => response.json().await.context("decoding JSON payload"),
+        Err(e) => Err(e),
+    };
+
+    let elapsed = started.elapsed();
+    let labels = [("endpoint", endpoint)];
+    API_POLL_SECONDS.add(&labels, elapsed.as_secs_f64());
+    API_POLL_LAST_SECONDS.set(&labels, elapsed.as_secs_f64());
+    let outcome = if result.is_ok() { "success" } else { "failure" };
+    API_POLLS.inc(&[("endpoint", endpoint), ("result", outcome)]);
+
+    if let Some(server_time) = server_time {
+        // assume the server read its clock halfway through the request
+        let local_time = sent_at + answered / 2;
+        let offset = server_time.with_timezone(&Local) - local_time;
+        CLOCK_OFFSET.set(&[], offset.num_milliseconds() as f64 / 1000.0);
+    }
+
+    result
+}
+
+#[derive(Deserialize)]
+struct ClarificationResponse {
+    id: String,
+    from_team_id: Option<String>,
+    to_team_id: Option<String>,
+    time: DateTime<FixedOffset>,
+    text: String,
+}
+
+/// Returns the most recent clarification sent by the jury to all teams.
+async fn fetch_latest_announcement(
+    client: &Client,
+    url: &str,
+) -> Result<Option<ClarificationResponse>> {
+    let payload: Vec<ClarificationResponse> = request(client, url, "clarifications").await?;
+
+    Ok(payload
+        .into_iter()
+        .filter(|c| c.from_team_id.is_none() && c.to_team_id.is_none())
+        .max_by_key(|c| c.time))
+}
+
+/// Polls the teams endpoint until the team of this machine is found.
+async fn poll_team(bus: &impl SystemSender, client: &Client, config: &ApiPollerConfig) {
+    let Some(url) = &config.teams_url else {
+        debug!("[Contest-Api] teams url not set, not looking up team");
+        return;
+    };
+
+    let poll_interval = Duration::from_secs(config.interval.max(0) as u64);
+
+    loop {
+        match fetch_teams(client, url).await {
+            Ok(teams) => {
+                // read every time, DHCP may hand out the address only after the greeter started
+                let hostname = host::hostname();
+                let addresses = host::ip_addresses()
+                    .into_iter()
+                    .map(|a| a.to_string())
+                    .collect::<Vec<_>>();
+                let team = teams.into_iter().find(|team| {
+                    (team.hostname.is_some() && team.hostname == hostname)
+                        || team.ip.as_ref().is_some_and(|ip| addresses.contains(ip))
+                });
+                match team {
+                    Some(team) => {
+                        let name = team.display_name.unwrap_or(team.name);
+                        info!("[Contest-Api] this machine belongs to team {name}");
+                        bus.send_to(CoreName::UI, UiMessage::SetTeamName(name));
+                        return;
+                    }
+                    None => debug!("[Contest-Api] no team found for this machine"),
+                }
+            }
+            Err(e) => debug!("[Contest-Api] failed to fetch teams ({url}): {:#}", e),
+        }
+
+        sleep(poll_interval).await;
+    }
+}
+
+#[derive(Deserialize)]
+struct TeamResponse {
+    name: String,
+    display_name: Option<String>,
+    hostname: Option<String>,
+    ip: Option<String>,
+}
+
+async fn fetch_teams(client: &Client, url: &str) -> Result<Vec<TeamResponse>> {
+    request(client, url, "teams").await
+}
+
+#[derive(Deserialize)]
+struct ContestApiResponse {
+    name: Option<String>,
+    formal_name: Option<String>,
+    start_time: DateTime<FixedOffset>,
+    #[serde(default, deserialize_with = "deserialize_reltime")]
+    duration: Option<TimeDelta>,
+    #[serde(default, deserialize_with = "deserialize_reltime")]
+    scoreboard_freeze_duration: Option<TimeDelta>,
+}
+
+struct Contest {
+    name: Option<String>,
+    start_time: DateTime<Local>,
+    duration: Option<TimeDelta>,
+    freeze_duration: Option<TimeDelta>,
+}
+
+impl Contest {
+    fn info(&self) -> ContestInfo {
+        ContestInfo {
+            name: self.name.clone(),
+            start_time: self.start_time,
+            duration: self.duration,
+        }
+    }
+
+    /// Without a duration the contest is considered running forever once it has started.
+    fn phase_at(&self, now: DateTime<Local>) -> ContestPhase {
+        if now < self.start_time {
+            return ContestPhase::BeforeStart;
+        }
+        let Some(duration) = self.duration else {
+            return ContestPhase::Running;
+        };
+
+        let end_time = self.start_time + duration;
+        let freeze_time = end_time - self.freeze_duration.unwrap_or_default();
+        if now >= end_time {
+            ContestPhase::Ended
+        } else if now >= freeze_time {
+            ContestPhase::Frozen
+        } else {
+            ContestPhase::Running
+        }
+    }
+}
+
+async fn fetch_contest(client: &Client, url: &str) -> Result<Contest> {
+    let payload: ContestApiResponse = request(client, url, "contest").await?;
+
+    Ok(Contest {
+        name: payload.formal_name.or(payload.name),
+        start_time: payload.start_time.with_timezone(&Local),
+        duration: payload.duration,
+        freeze_duration: payload.scoreboard_freeze_duration,
+    })
+}
+
+fn deserialize_reltime<'de, D>(deserializer: D) -> Result<Option<TimeDelta>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let opt = Option::<String>::deserialize(deserializer)?;
+    opt.map(|repr| parse_reltime(&repr).map_err(serde::de::Error::custom))
+        .transpose()
+}
 
-    let payload: ContestApiResponse = response.json().await.context("decoding JSON payload")?;
+/// Parses a CCS relative time of the form `(-)?(h)*h:mm:ss(.uuu)?`.
+pub(crate) fn parse_reltime(repr: &str) -> Result<TimeDelta> {
+    let (negative, rest) = match repr.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, repr),
+    };
+    let (hms, millis) = match rest.split_once('.') {
+        Some((hms, frac)) => {
+            let millis: String = frac.chars().chain(std::iter::repeat('0')).take(3).collect();
+            let millis = millis
+                .parse::<i64>()
+                .context(format!("invalid relative time {repr}"))?;
+            (hms, millis)
+        }
+        None => (rest, 0),
+    };
+
+    let parts = hms
+        .split(':')
+        .map(|part| part.parse::<i64>())
+        .collect::<Result<Vec<_>, _>>()
+        .context(format!("invalid relative time {repr}"))?;
+    let [hours, minutes, seconds] = parts[..] else {
+        return Err(anyhow!("invalid relative time {repr}"));
+    };
 
-    Ok(payload.start_time.with_timezone(&Local))
+    let delta = TimeDelta::milliseconds(((hours * 60 + minutes) * 60 + seconds) * 1000 + millis);
+    Ok(if negative { -delta } else { delta })
 }
@@ -1,19 +1,187 @@
-use chrono::{Local, TimeZone};
-use contest_greeter_dbus::{GreeterService, GreeterServiceBackend};
-use log::{error, info};
-use types::{GreeterMessage, SystemSender, UiMessage};
-use zbus::conn::Builder;
+use std::{collections::HashMap, future::Future, pin::Pin, sync::Arc};
+
+use chrono::{Local, TimeDelta, TimeZone};
+use contest_greeter_dbus::{Caller, GreeterService, GreeterServiceBackend, OBJECT_PATH};
+use log::{debug, error, info, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use types::{
+    Announcement, CoreName, DbusMessage, DbusStatus, GreeterMessage, LoginSource, Severity,
+    StatusMessage, SystemBus, SystemSender, UiMessage,
+};
+use zbus::{Connection, conn::Builder, fdo, zvariant::Value};
+
+use crate::{host, logging, status::query_status};
+
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct DbusConfig {
+    /// Users (names or uids) allowed to call the D-Bus control methods. Reading properties is
+    /// always allowed.
+    #[serde(default = "default_allowed_users")]
+    dbus_allowed_users: Vec<String>,
+
+    /// Groups (names or gids) whose members may call the D-Bus control methods.
+    #[serde(default)]
+    dbus_allowed_groups: Vec<String>,
+
+    /// Additionally require allowed callers other than root to pass polkit, using the action
+    /// `nl.luukblankenstijn.ContestGreeterService.<Method>` (e.g. `...ContestGreeterService.Login`).
+    /// The actions are installed by `install.sh` and need a polkit rule granting them.
+    #[serde(default)]
+    dbus_polkit: bool,
+}
+
+impl Default for DbusConfig {
+    fn default() -> Self {
+        Self {
+            dbus_allowed_users: default_allowed_users(),
+            dbus_allowed_groups: Vec::new(),
+            dbus_polkit: false,
+        }
+    }
+}
+
+fn default_allowed_users() -> Vec<String> {
+    vec!["root".into(), "lightdm".into()]
+}
+
+const POLKIT_ACTION_PREFIX: &str = "nl.luukblankenstijn.ContestGreeterService";
+
+/// The allowed users and groups resolved to ids.
+struct AccessPolicy {
+    uids: Vec<u32>,
+    gids: Vec<u32>,
+    polkit: bool,
+}
+
+impl AccessPolicy {
+    fn new(config: &DbusConfig) -> Self {
+        let uids = config
+            .dbus_allowed_users
+            .iter()
+            .filter_map(|name| {
+                let uid = host::user_id(name);
+                if uid.is_none() {
+                    warn!("[DBus-Service] unknown user {name} in dbus_allowed_users");
+                }
+                uid
+            })
+            .collect();
+        let gids = config
+            .dbus_allowed_groups
+            .iter()
+            .filter_map(|name| {
+                let gid = host::group_id(name);
+                if gid.is_none() {
+                    warn!("[DBus-Service] unknown group {name} in dbus_allowed_groups");
+                }
+                gid
+            })
+            .collect();
+        Self {
+            uids,
+            gids,
+            polkit: config.dbus_polkit,
+        }
+    }
+
+    fn allows(&self, uid: u32, gids: Option<Vec<u32>>) -> bool {
+        if self.uids.contains(&uid) {
+            return true;
+        }
+        if self.gids.is_empty() {
+            return false;
+        }
+        gids.unwrap_or_else(|| host::user_groups(uid))
+            .iter()
+            .any(|gid| self.gids.contains(gid))
+    }
+}
+
+/// Asks polkit whether the bus name `sender` may perform `action`, without interaction.
+async fn check_polkit(connection: &Connection, sender: &str, action: &str) -> zbus::Result<bool> {
+    let subject = (
+        "system-bus-name",
+        HashMap::from([("name", Value::from(sender))]),
+    );
+    let details: HashMap<&str, &str> = HashMap::new();
+    let reply = connection
+        .call_method(
+            Some("org.freedesktop.PolicyKit1"),
+            "/org/freedesktop/PolicyKit1/Authority",
+            Some("org.freedesktop.PolicyKit1.Authority"),
+            "CheckAuthorization",
+            &(subject, action, details, 0u32, ""),
+        )
+        .await?;
+    let (authorized, _challenge, _details): (bool, bool, HashMap<String, String>) =
+        reply.body().deserialize()?;
+    Ok(authorized)
+}
 
 struct GreeterDbusBackend<T: SystemSender> {
     bus: T,
+    policy: Arc<AccessPolicy>,
 }
 
 impl<T: SystemSender + Sync> GreeterServiceBackend for GreeterDbusBackend<T> {
+    fn authorize(
+        &self,
+        method: &'static str,
+        caller: Caller,
+        connection: Connection,
+    ) -> Pin<Box<dyn Future<Output = fdo::Result<()>> + Send>> {
+        let policy = self.policy.clone();
+        Box::pin(async move {
+            let denied = |reason: &str| {
+                warn!(
+                    "[DBus-Service] denied {method} to {} (uid {:?}, pid {:?}): {reason}",
+                    caller.sender, caller.uid, caller.pid
+                );
+                Err(fdo::Error::AccessDenied(format!(
+                    "{method} is not allowed: {reason}"
+                )))
+            };
+
+            let Some(uid) = caller.uid else {
+                return denied("unknown caller uid");
+            };
+            if !policy.allows(uid, caller.gids.clone()) {
+                return denied("user is not in dbus_allowed_users or dbus_allowed_groups");
+            }
+            if policy.polkit && uid != 0 {
+                let action = format!("{POLKIT_ACTION_PREFIX}.{method}");
+                match check_polkit(&connection, &caller.sender, &action).await {
+                    Ok(true) => {}
+                    Ok(false) => return denied(&format!("polkit denied {action}")),
+                    Err(e) => return denied(&format!("polkit check failed: {e}")),
+                }
+            }
+
+            debug!(
+                "[DBus-Service] allowed {method} to {} (uid {uid})",
+                caller.sender
+            );
+            Ok(())
+        })
+    }
+
     fn set_wallpaper_source(&self, url: String) {
         self.bus
             .send_to(types::CoreName::UI, UiMessage::SetWallpaper(Some(url)));
     }
 
+    fn set_wallpaper_playlist(&self, sources: Vec<String>, interval: u32) {
+        self.bus.send_to(
+            types::CoreName::UI,
+            UiMessage::SetWallpaperPlaylist {
+                sources,
+                interval: interval as u64,
+            },
+        );
+    }
+
     fn set_countdown_endtime(&self, end_time: i64) -> zbus::fdo::Result<()> {
         let datetime = match Local.timestamp_millis_opt(end_time) {
             chrono::offset::LocalResult::Single(result) => result,
@@ -45,39 +213,107 @@ impl<T: SystemSender + Sync> GreeterServiceBackend for GreeterDbusBackend<T> {
     }
 
     fn login(&self) {
+        self.bus.send_to(
+            types::CoreName::Greeter,
+            GreeterMessage::Login(LoginSource::DBus),
+        );
+    }
+
+    fn show_message(
+        &self,
+        title: String,
+        body: String,
+        severity: String,
+        timeout: u32,
+    ) -> zbus::fdo::Result<()> {
+        let severity = severity
+            .parse::<Severity>()
+            .map_err(zbus::fdo::Error::InvalidArgs)?;
+        let expires_at = (timeout > 0).then(|| Local::now() + TimeDelta::seconds(timeout as i64));
+        self.bus.send_to(
+            types::CoreName::UI,
+            UiMessage::ShowMessage(Announcement {
+                title,
+                body,
+                severity,
+                expires_at,
+            }),
+        );
+        Ok(())
+    }
+
+    fn clear_message(&self) {
+        self.bus
+            .send_to(types::CoreName::UI, UiMessage::ClearMessage);
+    }
+
+    fn set_log_level(&self, level: String) -> zbus::fdo::Result<()> {
+        logging::set_level(&level).map_err(|e| zbus::fdo::Error::InvalidArgs(format!("{e:#}")))
+    }
+
+    fn show_diagnostics(&self, visible: bool) {
         self.bus
-            .send_to(types::CoreName::Greeter, GreeterMessage::Login());
+            .send_to(CoreName::UI, UiMessage::ShowDiagnostics(visible));
+    }
+
+    fn wallpaper_status(&self) -> Pin<Box<dyn Future<Output = String> + Send>> {
+        let status = query_status(&self.bus);
+        Box::pin(async move {
+            status
+                .await
+                .map(|status| status.wallpaper.to_string())
+                .unwrap_or_else(|| "unknown".into())
+        })
     }
 }
 
-pub async fn run_dbus_service(bus: impl SystemSender) {
-    let greeter_service = GreeterService::new(GreeterDbusBackend { bus });
+pub async fn run_dbus_service<T: SystemBus + Sync>(bus: T, config: DbusConfig) {
+    bus.send_to(
+        CoreName::Status,
+        StatusMessage::SetDbus(DbusStatus::Starting),
+    );
+    let status_bus = bus.clone();
+    let report = move |status| status_bus.send_to(CoreName::Status, StatusMessage::SetDbus(status));
+
+    let (tx, mut rx) = mpsc::channel(16);
+    bus.register(CoreName::DBus, tx);
+    let policy = Arc::new(AccessPolicy::new(&config));
+    let greeter_service = GreeterService::new(GreeterDbusBackend { bus, policy });
     let result = Builder::system()
         .and_then(|b| b.name("nl.luukblankenstijn.ContestGreeterService"))
-        .and_then(|b| {
-            b.serve_at(
-                "/nl/luukblankenstijn/ContestGreeterService",
-                greeter_service,
-            )
-        });
+        .and_then(|b| b.serve_at(OBJECT_PATH, greeter_service));
 
     match result {
         Ok(builder) => {
             // Now we await the async build process
             match builder.build().await {
-                Ok(_connection) => {
+                Ok(connection) => {
                     info!(
                         "[DBus-Service] Service started: nl.luukblankenstijn.ContestGreeterService"
                     );
+                    report(DbusStatus::Registered(
+                        "nl.luukblankenstijn.ContestGreeterService".into(),
+                    ));
+                    while let Some(msg) = rx.recv().await {
+                        match msg {
+                            DbusMessage::WallpaperStatusChanged => {
+                                if let Err(e) = GreeterService::<GreeterDbusBackend<T>>::notify_wallpaper_status(&connection).await {
+                                    debug!("[DBus-Service] failed to signal the wallpaper status: {e}");
+                                }
+                            }
+                        }
+                    }
                     std::future::pending::<()>().await;
                 }
                 Err(e) => {
                     error!("[DBus-Service] Failed to build D-Bus connection: {}", e);
+                    report(DbusStatus::Failed(e.to_string()));
                 }
             }
         }
         Err(e) => {
             error!("[DBus-Service] configuration failed: {}", e);
+            report(DbusStatus::Failed(e.to_string()));
         }
     }
 }
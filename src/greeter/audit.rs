@@ -0,0 +1,129 @@
+use std::{fmt, fs::OpenOptions, io::Write, os::unix::fs::OpenOptionsExt, path::PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{Local, SecondsFormat};
+use log::{info, warn};
+use serde::Serialize;
+
+use crate::{host, metrics::LOGIN_EVENTS};
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditAction {
+    Login,
+    Authentication,
+    StartSession,
+    Reboot,
+}
+
+impl fmt::Display for AuditAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            AuditAction::Login => "login",
+            AuditAction::Authentication => "authentication",
+            AuditAction::StartSession => "start_session",
+            AuditAction::Reboot => "reboot",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    Accepted,
+    Rejected,
+    Deferred,
+    Succeeded,
+    Failed,
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Outcome::Accepted => "accepted",
+            Outcome::Rejected => "rejected",
+            Outcome::Deferred => "deferred",
+            Outcome::Succeeded => "succeeded",
+            Outcome::Failed => "failed",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// One event in the audit log.
+#[derive(Debug, Serialize)]
+pub struct AuditEntry {
+    pub action: AuditAction,
+    pub outcome: Outcome,
+    /// What triggered the action, `manual` for logins typed into the login panel.
+    pub source: String,
+    pub user: Option<String>,
+    pub reason: Option<String>,
+}
+
+#[derive(Serialize)]
+struct AuditLine<'a> {
+    time: String,
+    host: Option<&'a str>,
+    #[serde(flatten)]
+    entry: &'a AuditEntry,
+}
+
+/// Append-only JSON lines record of who or what triggered logins, authentication and session
+/// starts, so the login time of every machine can be proven afterwards.
+pub struct AuditLog {
+    path: Option<PathBuf>,
+    host: Option<String>,
+}
+
+impl AuditLog {
+    /// An empty `path` only logs the entries.
+    pub fn new(path: &str) -> Self {
+        Self {
+            path: (!path.is_empty()).then(|| PathBuf::from(path)),
+            host: host::hostname(),
+        }
+    }
+
+    pub fn record(&self, entry: AuditEntry) {
+        LOGIN_EVENTS.inc(&[
+            ("action", &entry.action.to_string()),
+            ("outcome", &entry.outcome.to_string()),
+            ("source", &entry.source),
+        ]);
+        info!(
+            target: "audit",
+            "[Greeter] {:?} {:?} source={} user={} reason={}",
+            entry.action,
+            entry.outcome,
+            entry.source,
+            entry.user.as_deref().unwrap_or("-"),
+            entry.reason.as_deref().unwrap_or("-"),
+        );
+        if let Err(e) = self.append(&entry) {
+            warn!("[Greeter] failed to write audit log: {e:#}");
+        }
+    }
+
+    fn append(&self, entry: &AuditEntry) -> Result<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let line = AuditLine {
+            time: Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+            host: self.host.as_deref(),
+            entry,
+        };
+        let mut json = serde_json::to_vec(&line)?;
+        json.push(b'\n');
+
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .mode(0o640)
+            .open(path)
+            .and_then(|mut file| file.write_all(&json))
+            .context(format!("appending to {}", path.display()))
+    }
+}
@@ -0,0 +1,43 @@
+use std::{fs, path::PathBuf};
+
+use anyhow::{Context, Result};
+use chrono::{DateTime, Local, TimeDelta};
+
+/// Timestamps of recent automatic logins, persisted so they survive greeter restarts.
+pub struct AutoLoginRecord {
+    path: PathBuf,
+    window: TimeDelta,
+}
+
+impl AutoLoginRecord {
+    pub fn new(path: impl Into<PathBuf>, window_seconds: u64) -> Self {
+        Self {
+            path: path.into(),
+            window: TimeDelta::seconds(window_seconds as i64),
+        }
+    }
+
+    /// Returns the logins that happened within the window before `now`.
+    /// A missing or unreadable record counts as no recent logins.
+    pub fn recent(&self, now: DateTime<Local>) -> Vec<i64> {
+        let since = (now - self.window).timestamp();
+        fs::read_to_string(&self.path)
+            .unwrap_or_default()
+            .lines()
+            .filter_map(|line| line.trim().parse::<i64>().ok())
+            .filter(|timestamp| *timestamp >= since)
+            .collect()
+    }
+
+    /// Appends `now` to the record, dropping entries that fell out of the window.
+    pub fn push(&self, now: DateTime<Local>) -> Result<()> {
+        let mut entries = self.recent(now);
+        entries.push(now.timestamp());
+
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent).context(format!("creating {}", parent.display()))?;
+        }
+        let contents: String = entries.iter().map(|t| format!("{t}\n")).collect();
+        fs::write(&self.path, contents).context(format!("writing {}", self.path.display()))
+    }
+}
@@ -272,6 +272,23 @@ impl Greeter {
         }
     }
 
+    /// Sets the language of the session of the authenticated user, e.g. `es_ES.utf8`.
+    pub fn set_language(&self, language: &str) -> Result<(), GreeterError> {
+        let language = CString::new(language)
+            .map_err(|_| GreeterError("language contained a NUL byte".into()))?;
+
+        unsafe {
+            let mut error: *mut GError = ptr::null_mut();
+            let ok = lightdm_sys::lightdm_greeter_set_language(
+                self.ptr.as_ptr(),
+                language.as_ptr(),
+                &mut error,
+            );
+
+            handle_gboolean(ok, error, "set_language")
+        }
+    }
+
     /// Convenience helpers for a couple of common hints.
     pub fn default_session_hint(&self) -> Option<String> {
         unsafe {
@@ -296,6 +313,130 @@ impl Greeter {
     }
 }
 
+/// Asks the system to reboot through LightDM's power management helpers.
+pub fn restart() -> Result<(), GreeterError> {
+    unsafe {
+        let mut error: *mut GError = ptr::null_mut();
+        let ok = lightdm_sys::lightdm_restart(&mut error);
+
+        handle_gboolean(ok, error, "restart")
+    }
+}
+
+/// A keyboard layout known to LightDM, `name` is e.g. `us` or `de\tnodeadkeys`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Layout {
+    pub name: String,
+    pub short_description: Option<String>,
+    pub description: Option<String>,
+}
+
+impl Layout {
+    /// Safety: `layout` must be a valid LightDMLayout.
+    unsafe fn from_ptr(layout: *mut lightdm_sys::LightDMLayout) -> Option<Self> {
+        unsafe {
+            Some(Layout {
+                name: c_string(lightdm_sys::lightdm_layout_get_name(layout))?,
+                short_description: c_string(lightdm_sys::lightdm_layout_get_short_description(
+                    layout,
+                )),
+                description: c_string(lightdm_sys::lightdm_layout_get_description(layout)),
+            })
+        }
+    }
+}
+
+/// Lists the keyboard layouts available on this machine.
+pub fn layouts() -> Vec<Layout> {
+    let mut layouts = Vec::new();
+    unsafe {
+        // the list and the layouts in it are owned by LightDM
+        let mut node = lightdm_sys::lightdm_get_layouts();
+        while !node.is_null() {
+            let data = (*node).data as *mut lightdm_sys::LightDMLayout;
+            if !data.is_null()
+                && let Some(layout) = Layout::from_ptr(data)
+            {
+                layouts.push(layout);
+            }
+            node = (*node).next;
+        }
+    }
+    layouts
+}
+
+/// The active keyboard layout.
+pub fn layout() -> Option<Layout> {
+    unsafe {
+        let layout = lightdm_sys::lightdm_get_layout();
+        if layout.is_null() {
+            None
+        } else {
+            Layout::from_ptr(layout)
+        }
+    }
+}
+
+/// Activates the keyboard layout called `name`.
+pub fn set_layout(name: &str) -> Result<(), GreeterError> {
+    unsafe {
+        let mut node = lightdm_sys::lightdm_get_layouts();
+        while !node.is_null() {
+            let data = (*node).data as *mut lightdm_sys::LightDMLayout;
+            if !data.is_null()
+                && c_string(lightdm_sys::lightdm_layout_get_name(data)).as_deref() == Some(name)
+            {
+                lightdm_sys::lightdm_set_layout(data);
+                return Ok(());
+            }
+            node = (*node).next;
+        }
+    }
+    Err(GreeterError(format!("unknown keyboard layout {name}")))
+}
+
+/// A language installed on this machine, `code` is a locale such as `pt_BR.utf8`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Language {
+    pub code: String,
+    pub name: Option<String>,
+}
+
+/// Lists the languages with an installed locale.
+pub fn languages() -> Vec<Language> {
+    let mut languages = Vec::new();
+    unsafe {
+        // the list and the languages in it are owned by LightDM
+        let mut node = lightdm_sys::lightdm_get_languages();
+        while !node.is_null() {
+            let data = (*node).data as *mut lightdm_sys::LightDMLanguage;
+            if !data.is_null()
+                && let Some(code) = c_string(lightdm_sys::lightdm_language_get_code(data))
+            {
+                languages.push(Language {
+                    code,
+                    name: c_string(lightdm_sys::lightdm_language_get_name(data)),
+                });
+            }
+            node = (*node).next;
+        }
+    }
+    languages
+}
+
+/// Safety: `ptr` must be null or a valid NUL-terminated string.
+unsafe fn c_string(ptr: *const libc::c_char) -> Option<String> {
+    if ptr.is_null() {
+        None
+    } else {
+        Some(
+            unsafe { CStr::from_ptr(ptr) }
+                .to_string_lossy()
+                .into_owned(),
+        )
+    }
+}
+
 impl Drop for Greeter {
     fn drop(&mut self) {
         self.disconnect_prompt_handler();
@@ -338,7 +479,7 @@ where
     unsafe {
         g_signal_connect_data(
             ptr.as_ptr() as *mut _,
-            b"show-prompt\0".as_ptr() as *const _,
+            c"show-prompt".as_ptr(),
             Some(std::mem::transmute::<
                 unsafe extern "C" fn(
                     *mut lightdm_sys::LightDMGreeter,
@@ -386,7 +527,7 @@ where
     unsafe {
         g_signal_connect_data(
             ptr.as_ptr() as *mut _,
-            b"show-message\0".as_ptr() as *const _,
+            c"show-message".as_ptr(),
             Some(std::mem::transmute::<
                 unsafe extern "C" fn(
                     *mut lightdm_sys::LightDMGreeter,
@@ -433,7 +574,7 @@ where
     unsafe {
         g_signal_connect_data(
             ptr.as_ptr() as *mut _,
-            b"authentication-complete\0".as_ptr() as *const _,
+            c"authentication-complete".as_ptr(),
             Some(std::mem::transmute::<
                 unsafe extern "C" fn(*mut lightdm_sys::LightDMGreeter, gpointer),
                 unsafe extern "C" fn(),
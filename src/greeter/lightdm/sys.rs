@@ -1,4 +1,4 @@
-use glib_sys::{GError, gboolean};
+use glib_sys::{GError, GList, gboolean};
 use libc::c_char;
 
 #[repr(C)]
@@ -23,6 +23,18 @@ pub struct LightDMGreeter {
     _opaque: [u8; 0],
 }
 
+#[repr(C)]
+pub struct LightDMLayout {
+    // GObject owned by LightDM, we never touch the fields directly.
+    _opaque: [u8; 0],
+}
+
+#[repr(C)]
+pub struct LightDMLanguage {
+    // GObject owned by LightDM, we never touch the fields directly.
+    _opaque: [u8; 0],
+}
+
 #[allow(dead_code)]
 #[link(name = "lightdm-gobject-1")]
 unsafe extern "C" {
@@ -72,7 +84,33 @@ unsafe extern "C" {
         error: *mut *mut GError,
     ) -> gboolean;
 
+    pub fn lightdm_greeter_set_language(
+        greeter: *mut LightDMGreeter,
+        language: *const c_char,
+        error: *mut *mut GError,
+    ) -> gboolean;
+
     pub fn lightdm_greeter_get_default_session_hint(greeter: *mut LightDMGreeter) -> *const c_char;
 
     pub fn lightdm_greeter_get_autologin_user_hint(greeter: *mut LightDMGreeter) -> *const c_char;
+
+    pub fn lightdm_restart(error: *mut *mut GError) -> gboolean;
+
+    pub fn lightdm_get_layouts() -> *mut GList;
+
+    pub fn lightdm_get_layout() -> *mut LightDMLayout;
+
+    pub fn lightdm_set_layout(layout: *mut LightDMLayout);
+
+    pub fn lightdm_layout_get_name(layout: *mut LightDMLayout) -> *const c_char;
+
+    pub fn lightdm_layout_get_short_description(layout: *mut LightDMLayout) -> *const c_char;
+
+    pub fn lightdm_layout_get_description(layout: *mut LightDMLayout) -> *const c_char;
+
+    pub fn lightdm_get_languages() -> *mut GList;
+
+    pub fn lightdm_language_get_code(language: *mut LightDMLanguage) -> *const c_char;
+
+    pub fn lightdm_language_get_name(language: *mut LightDMLanguage) -> *const c_char;
 }
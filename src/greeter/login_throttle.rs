@@ -0,0 +1,120 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+/// Failed manual logins, counted per username and over all usernames, with an exponentially
+/// growing delay before the next attempt is accepted. Counts are forgotten once no failure
+/// happened for the maximum delay, so mistakes spread over a contest don't add up to a lockout.
+pub struct LoginThrottle {
+    free_attempts: u32,
+    global_free_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    users: HashMap<String, Failures>,
+    global: Failures,
+}
+
+#[derive(Default)]
+struct Failures {
+    count: u32,
+    last: Option<Instant>,
+    locked_until: Option<Instant>,
+}
+
+impl Failures {
+    /// Whether no failure happened within `quiet`, any lockout has run out by then.
+    fn is_stale(&self, now: Instant, quiet: Duration) -> bool {
+        self.last
+            .is_none_or(|last| now.saturating_duration_since(last) >= quiet)
+    }
+
+    fn remaining(&self, now: Instant) -> Option<Duration> {
+        self.locked_until
+            .filter(|until| *until > now)
+            .map(|until| until - now)
+    }
+
+    /// Counts a failure and returns the delay before the next attempt.
+    fn fail(&mut self, now: Instant, free: u32, base: Duration, max: Duration) -> Duration {
+        if self.is_stale(now, max) {
+            self.count = 0;
+        }
+        self.count += 1;
+        self.last = Some(now);
+        let delay = match self.count.checked_sub(free) {
+            Some(over) if over > 0 => base
+                .checked_mul(2u32.saturating_pow(over - 1))
+                .unwrap_or(max)
+                .min(max),
+            _ => Duration::ZERO,
+        };
+        self.locked_until = Some(now + delay);
+        delay
+    }
+}
+
+impl LoginThrottle {
+    pub fn new(
+        free_attempts: u32,
+        global_free_attempts: u32,
+        base_delay_seconds: u64,
+        max_delay_seconds: u64,
+    ) -> Self {
+        Self {
+            free_attempts,
+            global_free_attempts,
+            base_delay: Duration::from_secs(base_delay_seconds),
+            max_delay: Duration::from_secs(max_delay_seconds),
+            users: HashMap::new(),
+            global: Failures::default(),
+        }
+    }
+
+    /// Changes the limits after a config reload, failures counted so far are kept.
+    pub fn set_limits(
+        &mut self,
+        free_attempts: u32,
+        global_free_attempts: u32,
+        base_delay_seconds: u64,
+        max_delay_seconds: u64,
+    ) {
+        self.free_attempts = free_attempts;
+        self.global_free_attempts = global_free_attempts;
+        self.base_delay = Duration::from_secs(base_delay_seconds);
+        self.max_delay = Duration::from_secs(max_delay_seconds);
+    }
+
+    /// Time left before `username` may try again, `None` when an attempt is allowed.
+    pub fn locked(&self, username: &str, now: Instant) -> Option<Duration> {
+        let user = self.users.get(username).and_then(|f| f.remaining(now));
+        let global = self.global.remaining(now);
+        user.max(global)
+    }
+
+    /// Records a failed attempt and returns the delay before the next one is accepted.
+    pub fn failure(&mut self, username: &str, now: Instant) -> Duration {
+        let max_delay = self.max_delay;
+        self.users
+            .retain(|_, failures| !failures.is_stale(now, max_delay));
+        let user = self.users.entry(username.to_string()).or_default().fail(
+            now,
+            self.free_attempts,
+            self.base_delay,
+            self.max_delay,
+        );
+        let global = self.global.fail(
+            now,
+            self.global_free_attempts,
+            self.base_delay,
+            self.max_delay,
+        );
+        user.max(global)
+    }
+
+    /// Forgets the failures of `username` after a successful login.
+    pub fn success(&mut self, username: &str) {
+        self.users.remove(username);
+        self.global = Failures::default();
+    }
+}
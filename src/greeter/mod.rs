@@ -1,14 +1,41 @@
+mod audit;
+mod autologin_record;
 mod lightdm;
+mod login_throttle;
+
+use std::{
+    collections::HashSet,
+    time::{Duration, Instant},
+};
 
 use anyhow::{Result, anyhow};
-use log::{error, info, warn};
+use audit::{AuditAction, AuditEntry, AuditLog, Outcome};
+use autologin_record::AutoLoginRecord;
+use chrono::Local;
+use log::{debug, error, info, warn};
+use login_throttle::LoginThrottle;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use tokio::sync::mpsc;
 use types::CoreName;
-use types::{GreeterMessage, SystemBus, UiMessage};
+use types::{
+    Announcement, AuthState, ContestPhase, GreeterMessage, KeyboardLayout, LoginSource, Severity,
+    StatusMessage, SystemBus, SystemSender, UiMessage,
+};
+
+use crate::conf::{CONF_PATH, Conf, changed_keys, get_conf, top_level};
+use crate::{i18n, logging};
 
-#[derive(Debug, Clone, Deserialize, Serialize, Default, JsonSchema)]
+/// Settings outside the greeter's own that a config reload applies, the others need a restart.
+const RELOADED_KEYS: [&str; 5] = [
+    "log_level",
+    "background_source",
+    "background_playlist",
+    "background_interval",
+    "countdown_end_time",
+];
+
+#[derive(Debug, Clone, Deserialize, Serialize, JsonSchema)]
 pub struct GreeterConfig {
     /// Session to start (defaults to LightDM's default when unset).
     session: Option<String>,
@@ -20,15 +47,197 @@ pub struct GreeterConfig {
     /// Password used for automatic login.
     #[serde(default)]
     password: String,
+
+    /// Maximum number of automatic logins within `autologin_window` before the greeter stops
+    /// and asks for staff attention. `0` disables the limit.
+    #[serde(default = "default_autologin_limit")]
+    autologin_limit: u32,
+
+    /// Window in seconds in which automatic logins are counted towards `autologin_limit`.
+    #[serde(default = "default_autologin_window")]
+    autologin_window: u64,
+
+    /// File used to remember recent automatic logins across greeter restarts.
+    #[serde(default = "default_autologin_record")]
+    autologin_record: String,
+
+    /// JSON lines file recording what triggered each login, authentication results and session
+    /// starts. Empty disables the file, the entries are still logged.
+    #[serde(default = "default_audit_log")]
+    audit_log: String,
+
+    /// Failed manual logins per username before further attempts are delayed.
+    #[serde(default = "default_login_free_attempts")]
+    login_free_attempts: u32,
+
+    /// Failed manual logins over all usernames before further attempts are delayed.
+    #[serde(default = "default_login_global_free_attempts")]
+    login_global_free_attempts: u32,
+
+    /// Delay in seconds after the first delayed failure, doubled after every further failure.
+    #[serde(default = "default_login_delay")]
+    login_delay: u64,
+
+    /// Maximum delay in seconds between manual login attempts. Failures are forgotten after this
+    /// long without a failed login.
+    #[serde(default = "default_login_max_delay")]
+    login_max_delay: u64,
+
+    /// Keyboard layout selected when the greeter starts, as named by LightDM (e.g. `us`, or
+    /// `de\tnodeadkeys` for a variant). Defaults to the system layout.
+    keyboard_layout: Option<String>,
+
+    /// Seconds a countdown login waits for the contest API to report the contest phase before
+    /// logging in anyway at the configured end time.
+    #[serde(default = "default_phase_wait")]
+    phase_wait: u64,
+}
+
+impl Default for GreeterConfig {
+    fn default() -> Self {
+        Self {
+            session: None,
+            username: String::new(),
+            password: String::new(),
+            autologin_limit: default_autologin_limit(),
+            autologin_window: default_autologin_window(),
+            autologin_record: default_autologin_record(),
+            audit_log: default_audit_log(),
+            login_free_attempts: default_login_free_attempts(),
+            login_global_free_attempts: default_login_global_free_attempts(),
+            login_delay: default_login_delay(),
+            login_max_delay: default_login_max_delay(),
+            keyboard_layout: None,
+            phase_wait: default_phase_wait(),
+        }
+    }
+}
+
+fn default_autologin_limit() -> u32 {
+    3
+}
+
+fn default_autologin_window() -> u64 {
+    600
+}
+
+fn default_autologin_record() -> String {
+    "/var/lib/lightdm/contest-greeter/autologins".into()
+}
+
+fn default_audit_log() -> String {
+    "/var/lib/lightdm/contest-greeter/audit.log".into()
+}
+
+fn default_login_free_attempts() -> u32 {
+    3
+}
+
+fn default_login_global_free_attempts() -> u32 {
+    10
+}
+
+fn default_login_delay() -> u64 {
+    5
+}
+
+fn default_login_max_delay() -> u64 {
+    300
+}
+
+fn default_phase_wait() -> u64 {
+    60
+}
+
+/// Source recorded for logins typed into the login panel.
+const MANUAL: &str = "manual";
+
+/// Login being authenticated, kept to attribute the authentication result.
+struct LoginAttempt {
+    user: String,
+    source: String,
+}
+
+fn lockout_message(wait: Duration) -> String {
+    i18n::tr_args(
+        "login-locked-out",
+        &[("seconds", wait.as_secs_f64().ceil().into())],
+    )
+}
+
+/// Finds the installed locale for a configured language, preferring an exact match (`pt_BR`)
+/// over one for the same language (`pt` matches `pt_PT.utf8`).
+fn session_language(language: &str) -> Option<String> {
+    let languages = lightdm::languages();
+    let without_encoding = |code: &str| code.split(['.', '@']).next().unwrap_or(code).to_string();
+    let wanted = without_encoding(&language.replace('-', "_"));
+    let primary = i18n::primary_language(language);
+
+    let found = languages
+        .iter()
+        .find(|l| l.code == language || without_encoding(&l.code) == wanted)
+        .or_else(|| {
+            languages
+                .iter()
+                .find(|l| primary.is_some() && i18n::primary_language(&l.code) == primary)
+        });
+    match found {
+        Some(found) => {
+            info!(
+                "[Greeter] sessions use language {} ({})",
+                found.code,
+                found.name.as_deref().unwrap_or("unnamed")
+            );
+            Some(found.code.clone())
+        }
+        None => {
+            let installed = languages
+                .iter()
+                .map(|l| l.code.as_str())
+                .collect::<Vec<_>>();
+            warn!(
+                "[Greeter] language {language} is not installed, sessions keep the default language (installed: {})",
+                installed.join(", ")
+            );
+            None
+        }
+    }
+}
+
+/// Tells the login panel which keyboard layouts exist and which one is active.
+fn send_layouts(bus: &impl SystemSender) {
+    let layouts = lightdm::layouts()
+        .into_iter()
+        .map(|layout| KeyboardLayout {
+            short: layout
+                .short_description
+                .unwrap_or_else(|| layout.name.clone()),
+            description: layout.description.unwrap_or_else(|| layout.name.clone()),
+            name: layout.name,
+        })
+        .collect();
+    let active = lightdm::layout().map(|layout| layout.name);
+    bus.send_to(
+        CoreName::UI,
+        UiMessage::SetKeyboardLayouts { layouts, active },
+    );
 }
 
 pub struct Greeter {
     greeter: lightdm::Greeter,
     conf: GreeterConfig,
+    /// The whole config file as last applied, to tell which settings a reload changes.
+    file: Conf,
+    autologin_record: AutoLoginRecord,
+    audit: AuditLog,
+    /// Locale passed to sessions, resolved from the configured language.
+    session_language: Option<String>,
+    /// The contest API reports the phase, countdown logins wait for it.
+    contest_api: bool,
 }
 
 impl Greeter {
-    pub fn new(conf: GreeterConfig) -> Result<Self> {
+    pub fn new(file: Conf, contest_api: bool) -> Result<Self> {
         let greeter = match lightdm::Greeter::new() {
             Ok(greeter) => {
                 if let Err(e) = greeter.connect_to_daemon() {
@@ -43,10 +252,22 @@ impl Greeter {
                 return Err(anyhow!("[Greeter] failed to construct greeter: {e}"));
             }
         };
-        Ok(Self { greeter, conf })
+        let conf = file.greeter.clone();
+        let autologin_record = AutoLoginRecord::new(&conf.autologin_record, conf.autologin_window);
+        let audit = AuditLog::new(&conf.audit_log);
+        let session_language = file.language.as_deref().and_then(session_language);
+        Ok(Self {
+            greeter,
+            conf,
+            file,
+            autologin_record,
+            audit,
+            session_language,
+            contest_api,
+        })
     }
 
-    pub async fn run(&self, bus: impl SystemBus) {
+    pub async fn run(&mut self, bus: impl SystemBus) {
         let message_bus = bus.clone();
         self.greeter
             .set_message_handler(move |message, message_type| {
@@ -56,46 +277,458 @@ impl Greeter {
                 }
                 if let lightdm::MessageType::Error = message_type {
                     message_bus.send_to(CoreName::UI, UiMessage::SetError(message.to_string()));
+                    message_bus.send_to(
+                        CoreName::Status,
+                        StatusMessage::ReportError(message.to_string()),
+                    );
                 }
             });
 
         let auth_bus = bus.clone();
-        let _conf = self.conf.clone();
         self.greeter
             .set_authentication_complete_handler(move |success| {
-                if success {
-                    auth_bus.send_to(
-                        CoreName::Greeter,
-                        GreeterMessage::StartSession(_conf.session.clone()),
-                    );
-                    info!("[Greeter] authentication succeeded");
-                } else {
-                    auth_bus.send_to(
-                        CoreName::UI,
-                        UiMessage::SetError("Authentication failed".to_string()),
-                    );
-                    warn!("[Greeter] authentication failed");
-                }
+                auth_bus.send_to(
+                    CoreName::Greeter,
+                    GreeterMessage::AuthenticationComplete(success),
+                );
             });
 
         let (tx, mut rx) = mpsc::channel(16);
         bus.register(CoreName::Greeter, tx);
 
+        if let Some(layout) = &self.conf.keyboard_layout
+            && let Err(e) = lightdm::set_layout(layout)
+        {
+            warn!("[Greeter] failed to set default keyboard layout: {e}");
+        }
+        send_layouts(&bus);
+
         info!("[Greeter] starting greeter loop");
-        let _conf = self.conf.clone();
+        let mut phase = None;
+        let mut deferred = None;
+        // the contest API did not report a phase in time, countdown logins go ahead without it
+        let mut phase_wait_expired = false;
+        let mut throttle = LoginThrottle::new(
+            self.conf.login_free_attempts,
+            self.conf.login_global_free_attempts,
+            self.conf.login_delay,
+            self.conf.login_max_delay,
+        );
+        let mut halted: Option<String> = None;
+        let mut attempt: Option<LoginAttempt> = None;
         while let Some(msg) = rx.recv().await {
             match msg {
                 GreeterMessage::LoginWithCreds(username, password) => {
+                    if let Some(wait) = throttle.locked(&username, Instant::now()) {
+                        self.audit(
+                            AuditAction::Login,
+                            Outcome::Rejected,
+                            MANUAL,
+                            Some(&username),
+                            Some(&format!("throttled for {}s", wait.as_secs())),
+                        );
+                        bus.send_to(CoreName::UI, UiMessage::SetError(lockout_message(wait)));
+                        continue;
+                    }
+                    self.audit(
+                        AuditAction::Login,
+                        Outcome::Accepted,
+                        MANUAL,
+                        Some(&username),
+                        None,
+                    );
+                    attempt = Some(LoginAttempt {
+                        user: username.clone(),
+                        source: MANUAL.into(),
+                    });
+                    bus.send_to(
+                        CoreName::Status,
+                        StatusMessage::SetAuthState(AuthState::Authenticating(username.clone())),
+                    );
                     self.authenticate(username, password)
                 }
-                GreeterMessage::StartSession(session_option) => self.start_session(session_option),
-                GreeterMessage::Login() => {
-                    self.authenticate(_conf.username.clone(), _conf.password.clone());
+                GreeterMessage::AuthenticationComplete(success) => {
+                    let Some(LoginAttempt { user, source }) = attempt.take() else {
+                        warn!("[Greeter] authentication completed without a login attempt");
+                        continue;
+                    };
+                    let outcome = if success {
+                        Outcome::Succeeded
+                    } else {
+                        Outcome::Failed
+                    };
+                    self.audit(
+                        AuditAction::Authentication,
+                        outcome,
+                        &source,
+                        Some(&user),
+                        None,
+                    );
+
+                    if success {
+                        info!("[Greeter] authentication succeeded");
+                        if source == MANUAL {
+                            throttle.success(&user);
+                        }
+                        self.start_session(&bus, self.conf.session.clone(), &source);
+                        continue;
+                    }
+
+                    warn!("[Greeter] authentication failed");
+                    bus.send_to(
+                        CoreName::Status,
+                        StatusMessage::SetAuthState(AuthState::Failed(user.clone())),
+                    );
+                    bus.send_to(
+                        CoreName::Status,
+                        StatusMessage::ReportError(format!("authentication failed for {user}")),
+                    );
+                    let mut error = i18n::tr("login-failed");
+                    if source == MANUAL {
+                        let delay = throttle.failure(&user, Instant::now());
+                        if !delay.is_zero() {
+                            error = lockout_message(delay);
+                        }
+                    }
+                    bus.send_to(CoreName::UI, UiMessage::SetError(error));
+                }
+                GreeterMessage::StartSession(session_option) => {
+                    self.start_session(&bus, session_option, "internal")
+                }
+                GreeterMessage::Login(source) => {
+                    if halted.is_some() {
+                        continue;
+                    }
+                    // a countdown from a stale end time must not log in after the contest ended
+                    if phase.is_none()
+                        && self.contest_api
+                        && !phase_wait_expired
+                        && source == LoginSource::Countdown
+                    {
+                        if deferred.replace(source).is_none() {
+                            self.defer_login(&bus, source);
+                        }
+                        continue;
+                    }
+                    let source = source.to_string();
+                    if phase == Some(ContestPhase::Ended) {
+                        info!("[Greeter] contest has ended, ignoring automatic login");
+                        self.audit(
+                            AuditAction::Login,
+                            Outcome::Rejected,
+                            &source,
+                            Some(&self.conf.username),
+                            Some("contest has ended"),
+                        );
+                        continue;
+                    }
+                    // the contest API asks again on every poll, these are not worth an audit entry
+                    if self.greeter.in_authentication() || self.greeter.is_authenticated() {
+                        debug!("[Greeter] login already in progress, ignoring automatic login");
+                        continue;
+                    }
+                    if let Err(message) = self.record_autologin() {
+                        bus.send_to(CoreName::UI, UiMessage::ShowStaffAttention(message.clone()));
+                        self.audit(
+                            AuditAction::Login,
+                            Outcome::Rejected,
+                            &source,
+                            Some(&self.conf.username),
+                            Some("automatic login limit reached"),
+                        );
+                        bus.send_to(
+                            CoreName::Status,
+                            StatusMessage::SetAuthState(AuthState::Halted),
+                        );
+                        bus.send_to(
+                            CoreName::Status,
+                            StatusMessage::ReportError("automatic login limit reached".into()),
+                        );
+                        halted = Some(message);
+                        continue;
+                    }
+                    self.audit(
+                        AuditAction::Login,
+                        Outcome::Accepted,
+                        &source,
+                        Some(&self.conf.username),
+                        None,
+                    );
+                    attempt = Some(LoginAttempt {
+                        user: self.conf.username.clone(),
+                        source,
+                    });
+                    bus.send_to(
+                        CoreName::Status,
+                        StatusMessage::SetAuthState(AuthState::Authenticating(
+                            self.conf.username.clone(),
+                        )),
+                    );
+                    self.authenticate(self.conf.username.clone(), self.conf.password.clone());
+                }
+                GreeterMessage::SetContestPhase(new_phase) => {
+                    phase = Some(new_phase);
+                    if let Some(source) = deferred.take() {
+                        bus.send_to(CoreName::UI, UiMessage::ClearMessage);
+                        bus.send_to(
+                            CoreName::Status,
+                            StatusMessage::SetAuthState(AuthState::Idle),
+                        );
+                        bus.send_to(CoreName::Greeter, GreeterMessage::Login(source));
+                    }
+                }
+                GreeterMessage::PhaseWaitExpired => {
+                    let Some(source) = deferred.take() else {
+                        continue;
+                    };
+                    warn!(
+                        "[Greeter] no contest phase after {}s, logging in at the configured end time",
+                        self.conf.phase_wait
+                    );
+                    bus.send_to(
+                        CoreName::Status,
+                        StatusMessage::ReportError(format!(
+                            "no contest phase after {}s, logged in without it",
+                            self.conf.phase_wait
+                        )),
+                    );
+                    phase_wait_expired = true;
+                    bus.send_to(CoreName::Greeter, GreeterMessage::Login(source));
+                }
+                GreeterMessage::SetKeyboardLayout(name) => {
+                    match lightdm::set_layout(&name) {
+                        Ok(()) => info!("[Greeter] keyboard layout set to {name}"),
+                        Err(e) => warn!("[Greeter] failed to set keyboard layout: {e}"),
+                    }
+                    send_layouts(&bus);
+                }
+                GreeterMessage::GetKeyboardLayouts => send_layouts(&bus),
+                GreeterMessage::UiStarted => {
+                    send_layouts(&bus);
+                    if let Some(message) = &halted {
+                        bus.send_to(CoreName::UI, UiMessage::ShowStaffAttention(message.clone()));
+                    }
+                }
+                GreeterMessage::ReloadConfig => match get_conf(CONF_PATH) {
+                    Ok(file) => self.reload_config(&bus, file, &mut throttle),
+                    Err(e) => {
+                        warn!("[Greeter] config reload failed: {e:#}");
+                        bus.send_to(
+                            CoreName::UI,
+                            UiMessage::ShowMessage(Announcement {
+                                title: i18n::tr("config-reload-failed-title"),
+                                body: format!("{e:#}"),
+                                severity: Severity::Critical,
+                                expires_at: Some(Local::now() + Duration::from_secs(30)),
+                            }),
+                        );
+                    }
+                },
+                GreeterMessage::Reboot => {
+                    info!("[Greeter] rebooting on staff request");
+                    let source = LoginSource::StaffChain.to_string();
+                    match lightdm::restart() {
+                        Ok(()) => {
+                            self.audit(AuditAction::Reboot, Outcome::Accepted, &source, None, None)
+                        }
+                        Err(e) => {
+                            error!("[Greeter] reboot failed: {e}");
+                            self.audit(
+                                AuditAction::Reboot,
+                                Outcome::Failed,
+                                &source,
+                                None,
+                                Some(&e.to_string()),
+                            );
+                            bus.send_to(
+                                CoreName::UI,
+                                UiMessage::SetError(i18n::tr_args(
+                                    "reboot-failed",
+                                    &[("error", e.to_string().into())],
+                                )),
+                            );
+                            bus.send_to(
+                                CoreName::Status,
+                                StatusMessage::ReportError(format!("reboot failed: {e}")),
+                            );
+                        }
+                    }
                 }
             }
         }
     }
 
+    /// Applies a re-read config file. The greeter's own settings, the log level, the wallpaper
+    /// and the countdown end time change right away, other changes need a greeter restart. The
+    /// login throttle counts and a stopped automatic login are kept.
+    fn reload_config(&mut self, bus: &impl SystemSender, file: Conf, throttle: &mut LoginThrottle) {
+        let changed = changed_keys(&self.file, &file);
+        info!(
+            "[Greeter] reloaded {CONF_PATH}, changed: {}",
+            changed.join(", ")
+        );
+        let is_changed = |key: &str| changed.iter().any(|k| k == key);
+
+        if is_changed("log_level")
+            && let Err(e) = logging::set_level(&file.log_level)
+        {
+            warn!("[Greeter] keeping the log level: {e:#}");
+        }
+        if is_changed("background_source")
+            || is_changed("background_playlist")
+            || is_changed("background_interval")
+        {
+            let wallpaper = if file.ui.background_playlist.is_empty() {
+                UiMessage::SetWallpaper(file.ui.background_source.clone())
+            } else {
+                UiMessage::SetWallpaperPlaylist {
+                    sources: file.ui.background_playlist.clone(),
+                    interval: file.ui.background_interval,
+                }
+            };
+            bus.send_to(CoreName::UI, wallpaper);
+        }
+        if is_changed("countdown_end_time") {
+            bus.send_to(
+                CoreName::UI,
+                UiMessage::SetCountdownEndtime {
+                    end_time: file.ui.countdown_end_time.map(|t| t.with_timezone(&Local)),
+                },
+            );
+        }
+        if is_changed("keyboard_layout")
+            && let Some(layout) = &file.greeter.keyboard_layout
+        {
+            if let Err(e) = lightdm::set_layout(layout) {
+                warn!("[Greeter] failed to set keyboard layout: {e}");
+            }
+            send_layouts(bus);
+        }
+
+        let greeter_keys: HashSet<String> = top_level(&self.conf)
+            .into_iter()
+            .chain(top_level(&file.greeter))
+            .map(|(key, _)| key)
+            .collect();
+        let conf = file.greeter.clone();
+        throttle.set_limits(
+            conf.login_free_attempts,
+            conf.login_global_free_attempts,
+            conf.login_delay,
+            conf.login_max_delay,
+        );
+        self.autologin_record = AutoLoginRecord::new(&conf.autologin_record, conf.autologin_window);
+        self.audit = AuditLog::new(&conf.audit_log);
+        self.conf = conf;
+        self.file = file;
+
+        let restart: Vec<&str> = changed
+            .iter()
+            .map(String::as_str)
+            .filter(|key| !RELOADED_KEYS.contains(key) && !greeter_keys.contains(*key))
+            .collect();
+        if !restart.is_empty() {
+            warn!(
+                "[Greeter] changes to {} take effect after a greeter restart",
+                restart.join(", ")
+            );
+        }
+        bus.send_to(
+            CoreName::UI,
+            UiMessage::ShowMessage(Announcement {
+                title: i18n::tr("config-reloaded-title"),
+                body: if restart.is_empty() {
+                    i18n::tr("config-reloaded")
+                } else {
+                    i18n::tr_args(
+                        "config-reloaded-restart",
+                        &[("settings", restart.join(", ").into())],
+                    )
+                },
+                severity: Severity::Info,
+                expires_at: Some(Local::now() + Duration::from_secs(30)),
+            }),
+        );
+    }
+
+    /// Holds back a countdown login until the contest API reports the phase, for at most
+    /// `phase_wait` seconds, and shows the wait to contestants and in the status.
+    fn defer_login(&self, bus: &impl SystemSender, source: LoginSource) {
+        let wait = Duration::from_secs(self.conf.phase_wait);
+        info!(
+            "[Greeter] contest phase not known yet, deferring automatic login for up to {}s",
+            wait.as_secs()
+        );
+        self.audit(
+            AuditAction::Login,
+            Outcome::Deferred,
+            &source.to_string(),
+            Some(&self.conf.username),
+            Some("waiting for the contest phase"),
+        );
+        bus.send_to(
+            CoreName::Status,
+            StatusMessage::SetAuthState(AuthState::WaitingForPhase),
+        );
+        bus.send_to(
+            CoreName::UI,
+            UiMessage::ShowMessage(Announcement {
+                title: i18n::tr("phase-wait-title"),
+                body: i18n::tr_args("phase-wait", &[("seconds", wait.as_secs().into())]),
+                severity: Severity::Warning,
+                expires_at: Some(Local::now() + wait),
+            }),
+        );
+        let bus = bus.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(wait).await;
+            bus.send_to(CoreName::Greeter, GreeterMessage::PhaseWaitExpired);
+        });
+    }
+
+    /// Records an automatic login attempt. When the configured limit is reached, returns the
+    /// message asking for staff attention instead.
+    fn record_autologin(&self) -> std::result::Result<(), String> {
+        let now = Local::now();
+        let recent = self.autologin_record.recent(now).len();
+        let limit = self.conf.autologin_limit as usize;
+        if limit > 0 && recent >= limit {
+            error!(
+                "[Greeter] {recent} automatic logins in the last {}s, stopping automatic login",
+                self.conf.autologin_window
+            );
+            return Err(i18n::tr_args(
+                "staff-attention-autologin",
+                &[
+                    ("attempts", recent.into()),
+                    ("minutes", self.conf.autologin_window.div_ceil(60).into()),
+                ],
+            ));
+        }
+
+        if let Err(e) = self.autologin_record.push(now) {
+            warn!("[Greeter] failed to record automatic login: {e:#}");
+        }
+        Ok(())
+    }
+
+    fn audit(
+        &self,
+        action: AuditAction,
+        outcome: Outcome,
+        source: &str,
+        user: Option<&str>,
+        reason: Option<&str>,
+    ) {
+        self.audit.record(AuditEntry {
+            action,
+            outcome,
+            source: source.to_string(),
+            user: user.map(str::to_string),
+            reason: reason.map(str::to_string),
+        });
+    }
+
     fn authenticate(&self, username: String, password: String) {
         self.greeter.respond_to_secret_prompts(password);
 
@@ -107,11 +740,42 @@ impl Greeter {
         };
     }
 
-    pub fn start_session(&self, session: Option<String>) {
+    pub fn start_session(&self, bus: &impl SystemSender, session: Option<String>, source: &str) {
+        let user = self.greeter.authentication_user();
+        if let Some(language) = &self.session_language
+            && let Err(e) = self.greeter.set_language(language)
+        {
+            warn!("[Greeter] failed to set session language {language}: {e}");
+        }
         match self.greeter.start_session(session.as_deref()) {
-            Ok(_) => {}
+            Ok(_) => {
+                self.audit(
+                    AuditAction::StartSession,
+                    Outcome::Succeeded,
+                    source,
+                    user.as_deref(),
+                    session.as_deref(),
+                );
+                bus.send_to(
+                    CoreName::Status,
+                    StatusMessage::SetAuthState(AuthState::SessionStarted(
+                        user.unwrap_or_default(),
+                    )),
+                );
+            }
             Err(e) => {
-                error!("[Greeter] failed to start session: {}", e)
+                error!("[Greeter] failed to start session: {}", e);
+                bus.send_to(
+                    CoreName::Status,
+                    StatusMessage::ReportError(format!("failed to start session: {e}")),
+                );
+                self.audit(
+                    AuditAction::StartSession,
+                    Outcome::Failed,
+                    source,
+                    user.as_deref(),
+                    Some(&e.to_string()),
+                );
             }
         }
     }
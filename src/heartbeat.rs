@@ -0,0 +1,100 @@
+use std::fs;
+
+use anyhow::{Context, Result};
+use chrono::{Local, SecondsFormat};
+use log::{debug, info};
+use reqwest::Client;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::time::{Duration, sleep};
+use types::SystemSender;
+
+use crate::status::{StatusReport, query_status};
+
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct HeartbeatConfig {
+    /// Collector URL the greeter periodically POSTs its status to as JSON. Disabled when unset.
+    heartbeat_url: Option<String>,
+
+    /// Seconds between heartbeats.
+    #[serde(default = "default_heartbeat_interval")]
+    heartbeat_interval: u64,
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        Self {
+            heartbeat_url: None,
+            heartbeat_interval: default_heartbeat_interval(),
+        }
+    }
+}
+
+fn default_heartbeat_interval() -> u64 {
+    30
+}
+
+#[derive(Serialize)]
+struct Heartbeat {
+    time: String,
+    version: &'static str,
+    /// SHA-256 of the configuration file, to spot seats running a different config.
+    config_hash: Option<String>,
+    #[serde(flatten)]
+    status: StatusReport,
+}
+
+/// Reports the status of this greeter to a collector, so the fleet can be overseen centrally.
+pub async fn run_heartbeat(bus: impl SystemSender, config: HeartbeatConfig, conf_path: &str) {
+    let Some(url) = &config.heartbeat_url else {
+        debug!("[Heartbeat] heartbeat url not set, not sending heartbeats");
+        return;
+    };
+
+    info!("[Heartbeat] reporting to {url}");
+    let client = Client::new();
+    let interval = Duration::from_secs(config.heartbeat_interval.max(1));
+    let config_hash = match config_hash(conf_path) {
+        Ok(hash) => Some(hash),
+        Err(e) => {
+            debug!("[Heartbeat] failed to hash config: {e:#}");
+            None
+        }
+    };
+
+    loop {
+        if let Some(status) = query_status(&bus).await {
+            let heartbeat = Heartbeat {
+                time: Local::now().to_rfc3339_opts(SecondsFormat::Secs, false),
+                version: env!("CARGO_PKG_VERSION"),
+                config_hash: config_hash.clone(),
+                status: StatusReport::new(&status),
+            };
+            if let Err(e) = send(&client, url, &heartbeat, interval).await {
+                debug!("[Heartbeat] failed to send heartbeat ({url}): {e:#}");
+            }
+        }
+
+        sleep(interval).await;
+    }
+}
+
+fn config_hash(path: &str) -> Result<String> {
+    let text = fs::read(path).context(format!("reading {path}"))?;
+    let digest = Sha256::digest(text);
+    Ok(digest.iter().map(|b| format!("{b:02x}")).collect())
+}
+
+async fn send(client: &Client, url: &str, heartbeat: &Heartbeat, timeout: Duration) -> Result<()> {
+    client
+        .post(url)
+        .timeout(timeout)
+        .json(heartbeat)
+        .send()
+        .await
+        .context(format!("sending request to {url}"))?
+        .error_for_status()
+        .context("server returned error")?;
+    Ok(())
+}
@@ -0,0 +1,132 @@
+use std::{
+    ffi::{CStr, CString},
+    mem,
+    net::{IpAddr, Ipv4Addr, Ipv6Addr},
+    ptr,
+};
+
+pub fn hostname() -> Option<String> {
+    let mut buf = [0u8; 256];
+    let ok = unsafe { libc::gethostname(buf.as_mut_ptr() as *mut libc::c_char, buf.len()) };
+    if ok != 0 {
+        return None;
+    }
+    let name = CStr::from_bytes_until_nul(&buf).ok()?;
+    Some(name.to_string_lossy().into_owned())
+}
+
+/// Lists the IPv4 and IPv6 addresses of all interfaces, loopback excluded.
+pub fn ip_addresses() -> Vec<IpAddr> {
+    interface_addresses()
+        .into_iter()
+        .map(|(_, address)| address)
+        .collect()
+}
+
+/// Lists the interface name and address pairs of all interfaces, loopback excluded.
+pub fn interface_addresses() -> Vec<(String, IpAddr)> {
+    let mut addresses = Vec::new();
+
+    unsafe {
+        let mut ifap: *mut libc::ifaddrs = ptr::null_mut();
+        if libc::getifaddrs(&mut ifap) != 0 {
+            return addresses;
+        }
+
+        let mut current = ifap;
+        while !current.is_null() {
+            let ifa = &*current;
+            current = ifa.ifa_next;
+            if ifa.ifa_addr.is_null() {
+                continue;
+            }
+
+            let address = match (*ifa.ifa_addr).sa_family as libc::c_int {
+                libc::AF_INET => {
+                    let sin = &*(ifa.ifa_addr as *const libc::sockaddr_in);
+                    IpAddr::V4(Ipv4Addr::from(u32::from_be(sin.sin_addr.s_addr)))
+                }
+                libc::AF_INET6 => {
+                    let sin6 = &*(ifa.ifa_addr as *const libc::sockaddr_in6);
+                    IpAddr::V6(Ipv6Addr::from(sin6.sin6_addr.s6_addr))
+                }
+                _ => continue,
+            };
+            if address.is_loopback() {
+                continue;
+            }
+
+            let name = CStr::from_ptr(ifa.ifa_name).to_string_lossy().into_owned();
+            addresses.push((name, address));
+        }
+
+        libc::freeifaddrs(ifap);
+    }
+
+    addresses
+}
+
+/// Resolves a user name, or a numeric uid, to a uid.
+pub fn user_id(name: &str) -> Option<u32> {
+    if let Ok(uid) = name.parse() {
+        return Some(uid);
+    }
+    let name = CString::new(name).ok()?;
+    let mut buf = vec![0 as libc::c_char; 4096];
+    unsafe {
+        let mut pwd: libc::passwd = mem::zeroed();
+        let mut result: *mut libc::passwd = ptr::null_mut();
+        let ok = libc::getpwnam_r(
+            name.as_ptr(),
+            &mut pwd,
+            buf.as_mut_ptr(),
+            buf.len(),
+            &mut result,
+        );
+        (ok == 0 && !result.is_null()).then_some(pwd.pw_uid)
+    }
+}
+
+/// Resolves a group name, or a numeric gid, to a gid.
+pub fn group_id(name: &str) -> Option<u32> {
+    if let Ok(gid) = name.parse() {
+        return Some(gid);
+    }
+    let name = CString::new(name).ok()?;
+    let mut buf = vec![0 as libc::c_char; 4096];
+    unsafe {
+        let mut grp: libc::group = mem::zeroed();
+        let mut result: *mut libc::group = ptr::null_mut();
+        let ok = libc::getgrnam_r(
+            name.as_ptr(),
+            &mut grp,
+            buf.as_mut_ptr(),
+            buf.len(),
+            &mut result,
+        );
+        (ok == 0 && !result.is_null()).then_some(grp.gr_gid)
+    }
+}
+
+/// Lists the groups `uid` is a member of, including its primary group.
+pub fn user_groups(uid: u32) -> Vec<u32> {
+    let mut buf = vec![0 as libc::c_char; 4096];
+    unsafe {
+        let mut pwd: libc::passwd = mem::zeroed();
+        let mut result: *mut libc::passwd = ptr::null_mut();
+        let ok = libc::getpwuid_r(uid, &mut pwd, buf.as_mut_ptr(), buf.len(), &mut result);
+        if ok != 0 || result.is_null() {
+            return Vec::new();
+        }
+
+        let mut groups = vec![0 as libc::gid_t; 64];
+        let mut count = groups.len() as libc::c_int;
+        if libc::getgrouplist(pwd.pw_name, pwd.pw_gid, groups.as_mut_ptr(), &mut count) < 0 {
+            // `count` now holds the number of groups
+            groups.resize(count as usize, 0);
+            libc::getgrouplist(pwd.pw_name, pwd.pw_gid, groups.as_mut_ptr(), &mut count);
+        }
+        groups.truncate(count.max(0) as usize);
+        groups
+    }
+}
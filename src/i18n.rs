@@ -0,0 +1,111 @@
+use std::sync::OnceLock;
+
+use fluent_bundle::{FluentArgs, FluentResource, FluentValue, concurrent::FluentBundle};
+use log::warn;
+use unic_langid::LanguageIdentifier;
+
+/// Catalogs compiled into the greeter, by language subtag.
+const CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../i18n/en.ftl")),
+    ("es", include_str!("../i18n/es.ftl")),
+    ("pt", include_str!("../i18n/pt.ftl")),
+    ("zh", include_str!("../i18n/zh.ftl")),
+];
+/// Used for languages without a catalog and messages missing from a catalog.
+const FALLBACK: &str = "en";
+
+type Bundle = FluentBundle<FluentResource>;
+
+struct Catalog {
+    selected: Option<Bundle>,
+    fallback: Bundle,
+}
+
+static CATALOG: OnceLock<Catalog> = OnceLock::new();
+
+/// Selects the language of the greeter's own text, e.g. `es`, `pt_BR.UTF-8` or `zh-CN`.
+pub fn init(language: Option<&str>) {
+    let selected = language.and_then(|language| {
+        let subtag = primary_language(language);
+        let catalog = CATALOGS
+            .iter()
+            .find(|(tag, _)| subtag.as_deref() == Some(*tag));
+        if catalog.is_none() {
+            warn!("[I18n] no translations for {language}, using English");
+        }
+        catalog
+            .filter(|(tag, _)| *tag != FALLBACK)
+            .map(|(tag, source)| bundle(tag, source))
+    });
+    let catalog = Catalog {
+        selected,
+        fallback: fallback_bundle(),
+    };
+    if CATALOG.set(catalog).is_err() {
+        warn!("[I18n] language already selected");
+    }
+}
+
+/// The language subtag of a locale or language tag, `pt_BR.UTF-8` gives `pt`.
+pub fn primary_language(locale: &str) -> Option<String> {
+    let tag = locale.split(['.', '@']).next()?.replace('_', "-");
+    let id: LanguageIdentifier = tag.parse().ok()?;
+    Some(id.language.as_str().to_string())
+}
+
+/// Translates the message `id`.
+pub fn tr(id: &str) -> String {
+    tr_args(id, &[])
+}
+
+/// Translates the message `id`, filling in its `{ $name }` placeables from `args`.
+pub fn tr_args<'a>(id: &str, args: &[(&'a str, FluentValue<'a>)]) -> String {
+    let catalog = CATALOG.get_or_init(|| Catalog {
+        selected: None,
+        fallback: fallback_bundle(),
+    });
+    let args = (!args.is_empty()).then(|| {
+        let mut fluent_args = FluentArgs::with_capacity(args.len());
+        for (name, value) in args {
+            fluent_args.set(*name, value.clone());
+        }
+        fluent_args
+    });
+
+    for bundle in catalog.selected.iter().chain([&catalog.fallback]) {
+        let Some(pattern) = bundle.get_message(id).and_then(|message| message.value()) else {
+            continue;
+        };
+        let mut errors = Vec::new();
+        let text = bundle.format_pattern(pattern, args.as_ref(), &mut errors);
+        if !errors.is_empty() {
+            warn!("[I18n] failed to format {id}: {errors:?}");
+        }
+        return text.into_owned();
+    }
+
+    warn!("[I18n] no translation for {id}");
+    id.to_string()
+}
+
+fn fallback_bundle() -> Bundle {
+    let (tag, source) = CATALOGS
+        .iter()
+        .find(|(tag, _)| *tag == FALLBACK)
+        .expect("fallback catalog");
+    bundle(tag, source)
+}
+
+fn bundle(tag: &str, source: &str) -> Bundle {
+    let resource = FluentResource::try_new(source.to_string()).unwrap_or_else(|(resource, e)| {
+        warn!("[I18n] errors in the {tag} catalog: {e:?}");
+        resource
+    });
+    let mut bundle = Bundle::new_concurrent(vec![tag.parse().unwrap_or_default()]);
+    // GTK lays out mixed direction text itself, isolation marks would only show up in logs
+    bundle.set_use_isolating(false);
+    if let Err(e) = bundle.add_resource(resource) {
+        warn!("[I18n] errors in the {tag} catalog: {e:?}");
+    }
+    bundle
+}
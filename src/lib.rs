@@ -3,6 +3,17 @@ pub mod conf;
 pub mod contest_api;
 pub mod dbus;
 pub mod greeter;
+pub mod heartbeat;
+pub mod host;
+pub mod i18n;
+pub mod logging;
+pub mod metrics;
+pub mod remote;
+pub mod status;
 pub mod ui;
 
-pub use types::{CoreName, GreeterMessage, ServiceChannel, SystemHandle, SystemMsg, UiMessage};
+pub use types::{
+    Announcement, AuthState, ContestInfo, ContestPhase, CoreName, DbusMessage, DbusStatus,
+    ErrorReport, GreeterMessage, GreeterStatus, KeyboardLayout, LoginSource, ServiceChannel,
+    Severity, SourceHealth, StatusMessage, SystemHandle, SystemMsg, UiMessage, WallpaperStatus,
+};
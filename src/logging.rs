@@ -0,0 +1,278 @@
+use std::{
+    collections::VecDeque,
+    io::{self, Write},
+    os::unix::net::UnixDatagram,
+    sync::{Mutex, OnceLock, RwLock},
+};
+
+use anyhow::{Result, anyhow};
+use chrono::{Local, SecondsFormat};
+use env_logger::Env;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+const JOURNAL_SOCKET: &str = "/run/systemd/journal/socket";
+const SYSLOG_IDENTIFIER: &str = "lightdm-contest-greeter";
+/// Number of log lines kept in memory for the diagnostics panel.
+const RECENT_LINES: usize = 200;
+
+/// Where log records are written to.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum LogOutput {
+    /// Plain text on stderr, which LightDM writes to the greeter log.
+    #[default]
+    Stderr,
+    /// Native journald entries with a `CORE` field per subsystem.
+    Journald,
+    /// JSON lines on stderr.
+    Json,
+}
+
+/// Filters with an env_logger filter that can be replaced at runtime, and writes the records
+/// to the configured output.
+struct GreeterLogger {
+    filter: RwLock<env_logger::Logger>,
+    output: LogOutput,
+    journal: Option<UnixDatagram>,
+    recent: Mutex<VecDeque<String>>,
+}
+
+static LOGGER: OnceLock<GreeterLogger> = OnceLock::new();
+
+/// Installs the logger. `RUST_LOG` takes precedence over `level`, as with env_logger.
+pub fn init(level: &str, output: LogOutput) {
+    let filter = env_logger::Builder::from_env(Env::default().default_filter_or(level)).build();
+    let max_level = filter.filter();
+
+    let mut journal_error = None;
+    let journal = match output {
+        LogOutput::Journald => match UnixDatagram::unbound()
+            .and_then(|socket| socket.connect(JOURNAL_SOCKET).map(|_| socket))
+        {
+            Ok(socket) => Some(socket),
+            Err(e) => {
+                journal_error = Some(e);
+                None
+            }
+        },
+        LogOutput::Stderr | LogOutput::Json => None,
+    };
+
+    let logger = LOGGER.get_or_init(|| GreeterLogger {
+        filter: RwLock::new(filter),
+        output,
+        journal,
+        recent: Mutex::new(VecDeque::with_capacity(RECENT_LINES)),
+    });
+    if log::set_logger(logger).is_ok() {
+        log::set_max_level(max_level);
+    }
+
+    if let Some(e) = journal_error {
+        log::warn!("[Main] failed to connect to journald, logging to stderr: {e}");
+    }
+}
+
+/// Replaces the log filter, using env_logger syntax (e.g. `debug` or `info,zbus=warn`).
+pub fn set_level(filters: &str) -> Result<()> {
+    validate(filters)?;
+    let logger = LOGGER.get().ok_or(anyhow!("logging is not initialised"))?;
+    let filter = env_logger::Builder::new().parse_filters(filters).build();
+    log::set_max_level(filter.filter());
+    *logger
+        .filter
+        .write()
+        .map_err(|_| anyhow!("log filter lock poisoned"))? = filter;
+    log::info!("[Main] log level set to {filters}");
+    Ok(())
+}
+
+/// The last `count` log lines, oldest first.
+pub fn recent_lines(count: usize) -> Vec<String> {
+    let Some(Ok(recent)) = LOGGER.get().map(|logger| logger.recent.lock()) else {
+        return Vec::new();
+    };
+    recent
+        .iter()
+        .skip(recent.len().saturating_sub(count))
+        .cloned()
+        .collect()
+}
+
+/// Log targets a directive may name, a directive for any other target would silently do nothing.
+const KNOWN_TARGETS: &[&str] = &[
+    "lightdm_contest_greeter",
+    "types",
+    "contest_greeter_dbus",
+    "audit",
+    "zbus",
+    "reqwest",
+    "hyper",
+    "hyper_util",
+    "axum",
+    "axum_server",
+    "rustls",
+    "tokio",
+];
+
+/// env_logger ignores invalid directives, check the levels and targets so a typo is reported to
+/// the caller.
+fn validate(filters: &str) -> Result<()> {
+    let directives = filters.split('/').next().unwrap_or_default();
+    if directives.trim().is_empty() {
+        return Err(anyhow!("empty log level"));
+    }
+    for directive in directives.split(',').map(str::trim) {
+        if directive.is_empty() {
+            continue;
+        }
+        let target = match directive.split_once('=') {
+            Some((target, level)) => {
+                level
+                    .parse::<LevelFilter>()
+                    .map_err(|_| anyhow!("invalid log level {level}"))?;
+                target
+            }
+            // a bare directive is either a level or a target logged at every level
+            None if directive.parse::<LevelFilter>().is_ok() => continue,
+            None => directive,
+        };
+        let known = KNOWN_TARGETS.iter().any(|known| {
+            target == *known
+                || target
+                    .strip_prefix(known)
+                    .is_some_and(|rest| rest.starts_with("::"))
+        });
+        if !known {
+            return Err(anyhow!("invalid log level or unknown module {target}"));
+        }
+    }
+    Ok(())
+}
+
+/// Splits the `[Core]` prefix off a message.
+fn split_core(message: &str) -> Option<(&str, &str)> {
+    let rest = message.strip_prefix('[')?;
+    let (core, message) = rest.split_once("] ")?;
+    (!core.contains(char::is_whitespace)).then_some((core, message))
+}
+
+impl GreeterLogger {
+    fn matches(&self, record: &Record) -> bool {
+        self.filter
+            .read()
+            .map(|filter| filter.matches(record))
+            .unwrap_or(false)
+    }
+
+    fn remember(&self, record: &Record) {
+        let Ok(mut recent) = self.recent.lock() else {
+            return;
+        };
+        if recent.len() == RECENT_LINES {
+            recent.pop_front();
+        }
+        recent.push_back(format!(
+            "{} {:<5} {}",
+            Local::now().format("%H:%M:%S"),
+            record.level(),
+            record.args()
+        ));
+    }
+
+    fn write_journal(&self, journal: &UnixDatagram, record: &Record) -> io::Result<()> {
+        let message = record.args().to_string();
+        let (core, message) = split_core(&message).unwrap_or((record.target(), &message));
+        let priority = match record.level() {
+            Level::Error => "3",
+            Level::Warn => "4",
+            Level::Info => "6",
+            Level::Debug | Level::Trace => "7",
+        };
+
+        let mut entry = Vec::new();
+        journal_field(&mut entry, "MESSAGE", message);
+        journal_field(&mut entry, "PRIORITY", priority);
+        journal_field(&mut entry, "CORE", core);
+        journal_field(&mut entry, "TARGET", record.target());
+        journal_field(&mut entry, "SYSLOG_IDENTIFIER", SYSLOG_IDENTIFIER);
+        if let Some(file) = record.file() {
+            journal_field(&mut entry, "CODE_FILE", file);
+        }
+        if let Some(line) = record.line() {
+            journal_field(&mut entry, "CODE_LINE", &line.to_string());
+        }
+        journal.send(&entry).map(|_| ())
+    }
+
+    fn write_json(&self, record: &Record) {
+        let message = record.args().to_string();
+        let (core, message) = split_core(&message).unwrap_or((record.target(), &message));
+        let line = JsonLine {
+            time: Local::now().to_rfc3339_opts(SecondsFormat::Millis, false),
+            level: record.level().as_str(),
+            core,
+            target: record.target(),
+            message,
+        };
+        if let Ok(mut json) = serde_json::to_vec(&line) {
+            json.push(b'\n');
+            let _ = io::stderr().lock().write_all(&json);
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonLine<'a> {
+    time: String,
+    level: &'a str,
+    core: &'a str,
+    target: &'a str,
+    message: &'a str,
+}
+
+/// Appends a field in the journald native protocol, which needs a length prefix for values
+/// spanning multiple lines.
+fn journal_field(entry: &mut Vec<u8>, key: &str, value: &str) {
+    entry.extend_from_slice(key.as_bytes());
+    if value.contains('\n') {
+        entry.push(b'\n');
+        entry.extend_from_slice(&(value.len() as u64).to_le_bytes());
+    } else {
+        entry.push(b'=');
+    }
+    entry.extend_from_slice(value.as_bytes());
+    entry.push(b'\n');
+}
+
+impl Log for GreeterLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.filter
+            .read()
+            .map(|filter| filter.enabled(metadata))
+            .unwrap_or(false)
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.matches(record) {
+            return;
+        }
+        self.remember(record);
+        if let Some(journal) = &self.journal
+            && self.write_journal(journal, record).is_ok()
+        {
+            return;
+        }
+        if self.output == LogOutput::Json {
+            self.write_json(record);
+        } else if let Ok(filter) = self.filter.read() {
+            filter.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        let _ = io::stderr().flush();
+    }
+}
@@ -3,28 +3,43 @@ mod conf;
 mod contest_api;
 mod dbus;
 mod greeter;
+mod heartbeat;
+mod host;
+mod i18n;
+mod logging;
+mod metrics;
+mod remote;
+mod status;
 mod ui;
 
-use env_logger::Env;
 use lightdm_contest_greeter::SystemHandle;
 use log::{error, info};
 use tokio::sync::mpsc;
 
-use ui::run_ui;
+use ui::{DiagnosticsInfo, run_ui};
 
 use crate::{
-    bus::start_bus, conf::get_conf, contest_api::run_api_poller, dbus::run_dbus_service,
+    bus::start_bus,
+    conf::{CONF_PATH, get_conf},
+    contest_api::run_api_poller,
+    dbus::run_dbus_service,
     greeter::Greeter,
+    heartbeat::run_heartbeat,
+    metrics::run_metrics,
+    remote::run_remote_control,
+    status::run_status,
 };
 
 #[tokio::main]
 async fn main() {
-    let config = match get_conf("/etc/lightdm/lightdm-contest-greeter.conf") {
+    let config = match get_conf(CONF_PATH) {
         Ok(config) => config,
         Err(e) => panic!("failed to read config: {e}"),
     };
 
-    env_logger::Builder::from_env(Env::default().default_filter_or(config.log_level)).init();
+    logging::init(&config.log_level, config.log_output);
+    i18n::init(config.language.as_deref());
+    let diagnostics = DiagnosticsInfo::new(&config);
 
     let (bus_tx, bus_rx) = mpsc::channel(16);
     let bus = SystemHandle::new(bus_tx);
@@ -37,13 +52,24 @@ async fn main() {
         rt.block_on(start_bus(bus_rx));
     });
 
+    let status_bus = bus.clone();
+    std::thread::spawn(move || {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("tokio runtime");
+        rt.block_on(run_status(status_bus));
+    });
+
     let greeter_bus = bus.clone();
+    let greeter_config = config.clone();
+    let contest_api = config.api_poller.url().is_some();
     std::thread::spawn(move || {
         let rt = tokio::runtime::Builder::new_current_thread()
             .enable_all()
             .build()
             .expect("tokio runtime");
-        let greeter = match Greeter::new(config.greeter) {
+        let mut greeter = match Greeter::new(greeter_config, contest_api) {
             Ok(greeter) => greeter,
             Err(e) => {
                 error!("[Main] failed to spawn greeter: {e}");
@@ -59,7 +85,12 @@ async fn main() {
             .enable_all()
             .build()
             .expect("tokio runtime");
-        rt.block_on(run_api_poller(api_bus, config.api_poller));
+        rt.block_on(async {
+            tokio::join!(
+                run_api_poller(api_bus.clone(), config.api_poller),
+                run_heartbeat(api_bus, config.heartbeat, CONF_PATH)
+            )
+        });
     });
 
     if config.enable_dbus {
@@ -69,11 +100,25 @@ async fn main() {
                 .enable_all()
                 .build()
                 .expect("tokio runtime");
-            rt.block_on(run_dbus_service(dbus_bus));
+            rt.block_on(run_dbus_service(dbus_bus, config.dbus));
         });
     }
 
-    run_ui(bus.clone(), config.ui).await;
+    let remote_bus = bus.clone();
+    std::thread::spawn(move || {
+        let rt = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("tokio runtime");
+        rt.block_on(async {
+            tokio::join!(
+                run_remote_control(remote_bus.clone(), config.remote),
+                run_metrics(remote_bus, config.metrics)
+            )
+        });
+    });
+
+    run_ui(bus.clone(), config.ui, diagnostics).await;
 
     info!("[Main] Greeter exiting");
 }
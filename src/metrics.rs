@@ -0,0 +1,213 @@
+use std::{
+    collections::BTreeMap,
+    fmt::Write,
+    net::SocketAddr,
+    sync::{LazyLock, Mutex},
+};
+
+use anyhow::{Context, Result};
+use axum::{Router, extract::State, http::header, response::IntoResponse, routing::get};
+use chrono::Local;
+use log::{error, info};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use types::SystemSender;
+
+use crate::status::query_status;
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+pub struct MetricsConfig {
+    /// Address and port serving Prometheus metrics at `/metrics` (e.g. `127.0.0.1:9464`).
+    /// Disabled when unset.
+    metrics_listen: Option<String>,
+}
+
+pub enum Kind {
+    Counter,
+    Gauge,
+}
+
+/// A metric family, its samples are kept in a process wide registry so every core can update
+/// them without passing a handle around.
+pub struct Metric {
+    name: &'static str,
+    help: &'static str,
+    kind: Kind,
+}
+
+pub static API_POLLS: Metric = Metric {
+    name: "contest_greeter_api_polls_total",
+    help: "Contest API requests by endpoint and result.",
+    kind: Kind::Counter,
+};
+
+pub static API_POLL_SECONDS: Metric = Metric {
+    name: "contest_greeter_api_poll_duration_seconds_total",
+    help: "Total time spent on contest API requests by endpoint.",
+    kind: Kind::Counter,
+};
+
+pub static API_POLL_LAST_SECONDS: Metric = Metric {
+    name: "contest_greeter_api_poll_last_duration_seconds",
+    help: "Duration of the last contest API request by endpoint.",
+    kind: Kind::Gauge,
+};
+
+pub static CLOCK_OFFSET: Metric = Metric {
+    name: "contest_greeter_clock_offset_seconds",
+    help: "Difference between the contest API server clock and the local clock.",
+    kind: Kind::Gauge,
+};
+
+pub static BUS_MESSAGES: Metric = Metric {
+    name: "contest_greeter_bus_messages_total",
+    help: "Messages on the internal bus by destination core and result.",
+    kind: Kind::Counter,
+};
+
+pub static LOGIN_EVENTS: Metric = Metric {
+    name: "contest_greeter_login_events_total",
+    help: "Login attempts, authentications and session starts by source and outcome.",
+    kind: Kind::Counter,
+};
+
+pub static WALLPAPER_FAILURES: Metric = Metric {
+    name: "contest_greeter_wallpaper_failures_total",
+    help: "Wallpapers that failed to load.",
+    kind: Kind::Counter,
+};
+
+pub static SECONDS_UNTIL_START: Metric = Metric {
+    name: "contest_greeter_seconds_until_start",
+    help: "Seconds until the countdown ends, negative once it has passed.",
+    kind: Kind::Gauge,
+};
+
+type Labels = Vec<(&'static str, String)>;
+
+static REGISTRY: LazyLock<Mutex<BTreeMap<&'static str, Family>>> = LazyLock::new(Mutex::default);
+
+struct Family {
+    metric: &'static Metric,
+    samples: BTreeMap<Labels, f64>,
+}
+
+impl Metric {
+    pub fn inc(&'static self, labels: &[(&'static str, &str)]) {
+        self.update(labels, |value| *value += 1.0);
+    }
+
+    pub fn add(&'static self, labels: &[(&'static str, &str)], amount: f64) {
+        self.update(labels, |value| *value += amount);
+    }
+
+    pub fn set(&'static self, labels: &[(&'static str, &str)], amount: f64) {
+        self.update(labels, |value| *value = amount);
+    }
+
+    pub fn get(&'static self, labels: &[(&'static str, &str)]) -> Option<f64> {
+        let registry = REGISTRY.lock().ok()?;
+        let labels: Labels = labels.iter().map(|(k, v)| (*k, v.to_string())).collect();
+        registry.get(self.name)?.samples.get(&labels).copied()
+    }
+
+    /// Removes all samples, so a gauge without a meaningful value is not exported.
+    pub fn clear(&'static self) {
+        if let Ok(mut registry) = REGISTRY.lock() {
+            registry.remove(self.name);
+        }
+    }
+
+    fn update(&'static self, labels: &[(&'static str, &str)], f: impl FnOnce(&mut f64)) {
+        let Ok(mut registry) = REGISTRY.lock() else {
+            return;
+        };
+        let family = registry.entry(self.name).or_insert_with(|| Family {
+            metric: self,
+            samples: BTreeMap::new(),
+        });
+        let labels = labels.iter().map(|(k, v)| (*k, v.to_string())).collect();
+        f(family.samples.entry(labels).or_default());
+    }
+}
+
+/// Renders all samples in the Prometheus text exposition format.
+fn render() -> String {
+    let mut out = String::new();
+    let Ok(registry) = REGISTRY.lock() else {
+        return out;
+    };
+    for family in registry.values() {
+        let metric = family.metric;
+        let kind = match metric.kind {
+            Kind::Counter => "counter",
+            Kind::Gauge => "gauge",
+        };
+        let _ = writeln!(out, "# HELP {} {}", metric.name, metric.help);
+        let _ = writeln!(out, "# TYPE {} {kind}", metric.name);
+        for (labels, value) in &family.samples {
+            let _ = writeln!(out, "{}{} {value}", metric.name, render_labels(labels));
+        }
+    }
+    out
+}
+
+fn render_labels(labels: &Labels) -> String {
+    if labels.is_empty() {
+        return String::new();
+    }
+    let labels = labels
+        .iter()
+        .map(|(k, v)| {
+            let v = v
+                .replace('\\', "\\\\")
+                .replace('"', "\\\"")
+                .replace('\n', "\\n");
+            format!("{k}=\"{v}\"")
+        })
+        .collect::<Vec<_>>();
+    format!("{{{}}}", labels.join(","))
+}
+
+pub async fn run_metrics(bus: impl SystemSender, config: MetricsConfig) {
+    let Some(listen) = &config.metrics_listen else {
+        info!("[Metrics] metrics_listen not set, not serving metrics");
+        return;
+    };
+    if let Err(e) = serve(bus, listen).await {
+        error!("[Metrics] metrics endpoint stopped: {e:#}");
+    }
+}
+
+async fn serve<T: SystemSender>(bus: T, listen: &str) -> Result<()> {
+    let address: SocketAddr = listen
+        .parse()
+        .context(format!("invalid metrics_listen {listen}"))?;
+    let app = Router::new()
+        .route("/metrics", get(metrics::<T>))
+        .with_state(bus);
+
+    let listener = tokio::net::TcpListener::bind(address)
+        .await
+        .context(format!("binding {address}"))?;
+    info!("[Metrics] serving metrics on {address}");
+    axum::serve(listener, app).await.context("serving")
+}
+
+async fn metrics<T: SystemSender>(State(bus): State<T>) -> impl IntoResponse {
+    let countdown_end = query_status(&bus)
+        .await
+        .and_then(|status| status.countdown_end);
+    match countdown_end {
+        Some(end) => {
+            let seconds = (end - Local::now()).num_milliseconds() as f64 / 1000.0;
+            SECONDS_UNTIL_START.set(&[], seconds);
+        }
+        None => SECONDS_UNTIL_START.clear(),
+    }
+
+    (
+        [(header::CONTENT_TYPE, "text/plain; version=0.0.4")],
+        render(),
+    )
+}
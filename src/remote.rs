@@ -0,0 +1,412 @@
+use std::{
+    collections::HashMap,
+    fs,
+    net::{IpAddr, SocketAddr},
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use anyhow::{Context, Result, anyhow};
+use axum::{
+    Json, Router,
+    extract::{ConnectInfo, Request, State},
+    http::{StatusCode, header},
+    middleware::{self, Next},
+    response::{IntoResponse, Response},
+    routing::{get, post},
+};
+use axum_server::tls_rustls::RustlsConfig;
+use chrono::{DateTime, FixedOffset, Local, TimeDelta};
+use log::{error, info, warn};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use types::{
+    Announcement, CoreName, GreeterMessage, LoginSource, Severity, SystemSender, UiMessage,
+};
+
+use crate::{
+    logging,
+    status::{StatusReport, query_status},
+};
+
+/// Shorter tokens are refused, the API can log in and reboot seats.
+const MIN_TOKEN_LENGTH: usize = 16;
+/// Bad tokens accepted from one address before its bad tokens are refused for a growing delay.
+const FREE_ATTEMPTS: u32 = 5;
+const BASE_DELAY: Duration = Duration::from_secs(1);
+/// Longest delay, an address quiet for this long is forgotten.
+const MAX_DELAY: Duration = Duration::from_secs(300);
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+pub struct RemoteConfig {
+    /// Address and port the remote control API listens on (e.g. `10.1.0.12:8421`). Use the
+    /// address of the interface facing the contest control network. Disabled when unset.
+    remote_listen: Option<String>,
+
+    /// File holding the shared token clients send as `Authorization: Bearer <token>`, at least
+    /// 16 characters. Required to enable the remote control API; keep it readable by the greeter
+    /// user only.
+    remote_token_file: Option<String>,
+
+    /// PEM certificate chain for serving the remote control API over HTTPS. Required with
+    /// `remote_tls_key` unless `remote_listen` is a loopback address.
+    remote_tls_cert: Option<String>,
+
+    /// PEM private key of `remote_tls_cert`.
+    remote_tls_key: Option<String>,
+}
+
+#[derive(Clone)]
+struct RemoteState<T: SystemSender> {
+    bus: T,
+    token: String,
+    bad_tokens: Arc<Mutex<BadTokens>>,
+}
+
+/// Bad tokens per client address. Only failures are limited, so a client sending the right
+/// token is never refused, and no address can lock out the others.
+#[derive(Default)]
+struct BadTokens {
+    clients: HashMap<IpAddr, BadTokenClient>,
+}
+
+struct BadTokenClient {
+    count: u32,
+    last: Instant,
+    locked_until: Instant,
+}
+
+impl BadTokens {
+    /// Time left before a bad token from `client` is answered again, `None` when it is.
+    fn locked(&mut self, client: IpAddr, now: Instant) -> Option<Duration> {
+        self.prune(now);
+        self.clients
+            .get(&client)
+            .map(|c| c.locked_until.saturating_duration_since(now))
+            .filter(|wait| !wait.is_zero())
+    }
+
+    /// Records a bad token and returns the delay before the next one is answered.
+    fn failure(&mut self, client: IpAddr, now: Instant) -> Duration {
+        self.prune(now);
+        let entry = self.clients.entry(client).or_insert(BadTokenClient {
+            count: 0,
+            last: now,
+            locked_until: now,
+        });
+        entry.count += 1;
+        entry.last = now;
+        let delay = match entry.count.checked_sub(FREE_ATTEMPTS) {
+            Some(over) if over > 0 => BASE_DELAY
+                .checked_mul(2u32.saturating_pow(over - 1))
+                .unwrap_or(MAX_DELAY)
+                .min(MAX_DELAY),
+            _ => Duration::ZERO,
+        };
+        entry.locked_until = now + delay;
+        delay
+    }
+
+    fn success(&mut self, client: IpAddr) {
+        self.clients.remove(&client);
+    }
+
+    /// Forgets addresses without a bad token for longer than the longest delay, their lockout
+    /// has expired by then.
+    fn prune(&mut self, now: Instant) {
+        self.clients
+            .retain(|_, c| now.saturating_duration_since(c.last) < MAX_DELAY);
+    }
+}
+
+/// HTTP control endpoint mirroring the D-Bus methods, so seats can be controlled centrally.
+pub async fn run_remote_control(bus: impl SystemSender, config: RemoteConfig) {
+    let Some(listen) = &config.remote_listen else {
+        info!("[Remote] remote_listen not set, not running remote control");
+        return;
+    };
+    if let Err(e) = serve(bus, listen, &config).await {
+        error!("[Remote] remote control stopped: {e:#}");
+    }
+}
+
+async fn serve(bus: impl SystemSender, listen: &str, config: &RemoteConfig) -> Result<()> {
+    let address: SocketAddr = listen
+        .parse()
+        .context(format!("invalid remote_listen {listen}"))?;
+    let token = read_token(config.remote_token_file.as_deref())?;
+    let tls = match (&config.remote_tls_cert, &config.remote_tls_key) {
+        (Some(cert), Some(key)) => Some((cert, key)),
+        (None, None) if address.ip().is_loopback() => None,
+        (None, None) => {
+            return Err(anyhow!(
+                "remote_listen {address} is reachable from the network, \
+                 set remote_tls_cert and remote_tls_key to serve it over HTTPS"
+            ));
+        }
+        _ => {
+            return Err(anyhow!(
+                "remote_tls_cert and remote_tls_key must be set together"
+            ));
+        }
+    };
+    let state = RemoteState {
+        bus,
+        token,
+        bad_tokens: Arc::new(Mutex::new(BadTokens::default())),
+    };
+
+    let app = Router::new()
+        .route("/status", get(status))
+        .route("/wallpaper", post(set_wallpaper))
+        .route("/wallpaper/playlist", post(set_wallpaper_playlist))
+        .route("/countdown", post(set_countdown).delete(disable_countdown))
+        .route("/login", post(login))
+        .route("/message", post(show_message).delete(clear_message))
+        .route("/log-level", post(set_log_level))
+        .route(
+            "/diagnostics",
+            post(show_diagnostics).delete(hide_diagnostics),
+        )
+        .route_layer(middleware::from_fn_with_state(state.clone(), authenticate))
+        .with_state(state)
+        .into_make_service_with_connect_info::<SocketAddr>();
+
+    let Some((cert, key)) = tls else {
+        let listener = tokio::net::TcpListener::bind(address)
+            .await
+            .context(format!("binding {address}"))?;
+        info!("[Remote] listening on {address}");
+        return axum::serve(listener, app).await.context("serving");
+    };
+
+    // reqwest brings the same provider, installing it fails harmlessly when it already is
+    let _ = rustls::crypto::ring::default_provider().install_default();
+    let tls = RustlsConfig::from_pem_file(cert, key)
+        .await
+        .context(format!("loading {cert} and {key}"))?;
+    info!("[Remote] listening on {address} (https)");
+    axum_server::bind_rustls(address, tls)
+        .serve(app)
+        .await
+        .context("serving")
+}
+
+fn read_token(path: Option<&str>) -> Result<String> {
+    let path = path.ok_or(anyhow!("remote_token_file is required"))?;
+    let token = fs::read_to_string(path).context(format!("reading {path}"))?;
+    let token = token.trim();
+    if token.chars().count() < MIN_TOKEN_LENGTH {
+        return Err(anyhow!(
+            "the token in {path} is shorter than {MIN_TOKEN_LENGTH} characters"
+        ));
+    }
+    Ok(token.to_string())
+}
+
+async fn authenticate<T: SystemSender>(
+    State(state): State<RemoteState<T>>,
+    ConnectInfo(client): ConnectInfo<SocketAddr>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let client = client.ip();
+    let provided = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "));
+    match provided {
+        Some(token) if constant_time_eq(token.as_bytes(), state.token.as_bytes()) => {
+            state.bad_tokens.lock().unwrap().success(client);
+            next.run(request).await
+        }
+        _ => {
+            let mut bad_tokens = state.bad_tokens.lock().unwrap();
+            let now = Instant::now();
+            if let Some(wait) = bad_tokens.locked(client, now) {
+                return (
+                    StatusCode::TOO_MANY_REQUESTS,
+                    [(header::RETRY_AFTER, wait.as_secs().max(1).to_string())],
+                )
+                    .into_response();
+            }
+            let delay = bad_tokens.failure(client, now);
+            drop(bad_tokens);
+            warn!(
+                "[Remote] rejected unauthenticated {} {} from {client}{}",
+                request.method(),
+                request.uri().path(),
+                if delay.is_zero() {
+                    String::new()
+                } else {
+                    format!(", refusing it for {}s", delay.as_secs())
+                }
+            );
+            StatusCode::UNAUTHORIZED.into_response()
+        }
+    }
+}
+
+/// Compares without stopping at the first difference, so the token can't be guessed from timing.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+async fn status<T: SystemSender>(
+    State(state): State<RemoteState<T>>,
+) -> Result<Json<StatusReport>, StatusCode> {
+    let status = query_status(&state.bus)
+        .await
+        .ok_or(StatusCode::SERVICE_UNAVAILABLE)?;
+    Ok(Json(StatusReport::new(&status)))
+}
+
+#[derive(Deserialize)]
+struct WallpaperRequest {
+    source: String,
+}
+
+async fn set_wallpaper<T: SystemSender>(
+    State(state): State<RemoteState<T>>,
+    Json(request): Json<WallpaperRequest>,
+) -> StatusCode {
+    info!("[Remote] setting wallpaper to {}", request.source);
+    state
+        .bus
+        .send_to(CoreName::UI, UiMessage::SetWallpaper(Some(request.source)));
+    StatusCode::NO_CONTENT
+}
+
+#[derive(Deserialize)]
+struct PlaylistRequest {
+    sources: Vec<String>,
+    interval: u64,
+}
+
+async fn set_wallpaper_playlist<T: SystemSender>(
+    State(state): State<RemoteState<T>>,
+    Json(request): Json<PlaylistRequest>,
+) -> StatusCode {
+    info!(
+        "[Remote] setting wallpaper playlist of {} sources",
+        request.sources.len()
+    );
+    state.bus.send_to(
+        CoreName::UI,
+        UiMessage::SetWallpaperPlaylist {
+            sources: request.sources,
+            interval: request.interval,
+        },
+    );
+    StatusCode::NO_CONTENT
+}
+
+#[derive(Deserialize)]
+struct CountdownRequest {
+    /// RFC3339 time the countdown ends and the session is started.
+    end_time: DateTime<FixedOffset>,
+}
+
+async fn set_countdown<T: SystemSender>(
+    State(state): State<RemoteState<T>>,
+    Json(request): Json<CountdownRequest>,
+) -> StatusCode {
+    let end_time = request.end_time.with_timezone(&Local);
+    info!("[Remote] setting countdown end time to {end_time}");
+    state.bus.send_to(
+        CoreName::UI,
+        UiMessage::SetCountdownEndtime {
+            end_time: Some(end_time),
+        },
+    );
+    StatusCode::NO_CONTENT
+}
+
+async fn disable_countdown<T: SystemSender>(State(state): State<RemoteState<T>>) -> StatusCode {
+    info!("[Remote] disabling countdown");
+    state.bus.send_to(
+        CoreName::UI,
+        UiMessage::SetCountdownEndtime { end_time: None },
+    );
+    StatusCode::NO_CONTENT
+}
+
+async fn login<T: SystemSender>(State(state): State<RemoteState<T>>) -> StatusCode {
+    info!("[Remote] login requested");
+    state.bus.send_to(
+        CoreName::Greeter,
+        GreeterMessage::Login(LoginSource::Remote),
+    );
+    StatusCode::NO_CONTENT
+}
+
+#[derive(Deserialize)]
+struct MessageRequest {
+    title: String,
+    body: String,
+    #[serde(default = "default_severity")]
+    severity: String,
+    /// Seconds until the message disappears, 0 keeps it until cleared.
+    #[serde(default)]
+    timeout: u32,
+}
+
+fn default_severity() -> String {
+    "info".into()
+}
+
+async fn show_message<T: SystemSender>(
+    State(state): State<RemoteState<T>>,
+    Json(request): Json<MessageRequest>,
+) -> Response {
+    let severity = match request.severity.parse::<Severity>() {
+        Ok(severity) => severity,
+        Err(e) => return (StatusCode::BAD_REQUEST, e).into_response(),
+    };
+    let expires_at =
+        (request.timeout > 0).then(|| Local::now() + TimeDelta::seconds(request.timeout as i64));
+    info!("[Remote] showing message {}", request.title);
+    state.bus.send_to(
+        CoreName::UI,
+        UiMessage::ShowMessage(Announcement {
+            title: request.title,
+            body: request.body,
+            severity,
+            expires_at,
+        }),
+    );
+    StatusCode::NO_CONTENT.into_response()
+}
+
+async fn clear_message<T: SystemSender>(State(state): State<RemoteState<T>>) -> StatusCode {
+    info!("[Remote] clearing message");
+    state.bus.send_to(CoreName::UI, UiMessage::ClearMessage);
+    StatusCode::NO_CONTENT
+}
+
+#[derive(Deserialize)]
+struct LogLevelRequest {
+    level: String,
+}
+
+async fn set_log_level(Json(request): Json<LogLevelRequest>) -> Response {
+    match logging::set_level(&request.level) {
+        Ok(()) => StatusCode::NO_CONTENT.into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, format!("{e:#}")).into_response(),
+    }
+}
+
+async fn show_diagnostics<T: SystemSender>(State(state): State<RemoteState<T>>) -> StatusCode {
+    state
+        .bus
+        .send_to(CoreName::UI, UiMessage::ShowDiagnostics(true));
+    StatusCode::NO_CONTENT
+}
+
+async fn hide_diagnostics<T: SystemSender>(State(state): State<RemoteState<T>>) -> StatusCode {
+    state
+        .bus
+        .send_to(CoreName::UI, UiMessage::ShowDiagnostics(false));
+    StatusCode::NO_CONTENT
+}
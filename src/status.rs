@@ -0,0 +1,107 @@
+use std::future::Future;
+
+use chrono::{Local, SecondsFormat};
+use log::info;
+use serde::Serialize;
+use tokio::sync::{mpsc, oneshot};
+use types::{
+    CoreName, DbusMessage, ErrorReport, GreeterStatus, SourceHealth, StatusMessage, SystemBus,
+    SystemSender, WallpaperStatus,
+};
+
+use crate::host;
+
+/// Keeps track of the runtime state reported by the other cores, so it can be queried by the
+/// control surfaces.
+pub async fn run_status(bus: impl SystemBus) {
+    let (tx, mut rx) = mpsc::channel(16);
+    bus.register(CoreName::Status, tx);
+
+    info!("[Status] starting status loop");
+    let mut status = GreeterStatus::default();
+    while let Some(msg) = rx.recv().await {
+        match msg {
+            StatusMessage::SetWallpaper(wallpaper) => {
+                if let WallpaperStatus::Invalid { .. } = wallpaper {
+                    status.last_error = Some(error_report(format!("wallpaper {wallpaper}")));
+                }
+                if status.wallpaper != wallpaper {
+                    status.wallpaper = wallpaper;
+                    bus.send_to(CoreName::DBus, DbusMessage::WallpaperStatusChanged);
+                }
+            }
+            StatusMessage::SetCountdownEndtime(end_time) => status.countdown_end = end_time,
+            StatusMessage::SetContestPhase(phase) => status.contest_phase = Some(phase),
+            StatusMessage::SetContestApi(health) => {
+                if let SourceHealth::Failing(reason) = &health
+                    && status.contest_api != health
+                {
+                    status.last_error = Some(error_report(format!("contest api: {reason}")));
+                }
+                status.contest_api = health;
+                status.contest_api_polled = Some(Local::now());
+            }
+            StatusMessage::SetDbus(dbus) => status.dbus = dbus,
+            StatusMessage::SetAuthState(auth) => status.auth = auth,
+            StatusMessage::ReportError(message) => status.last_error = Some(error_report(message)),
+            StatusMessage::Get(reply) => {
+                let _ = reply.send(status.clone());
+            }
+        }
+    }
+}
+
+fn error_report(message: String) -> ErrorReport {
+    ErrorReport {
+        time: Local::now(),
+        message,
+    }
+}
+
+/// Asks the status core for the current state, `None` when it is not running.
+pub fn query_status(
+    bus: &impl SystemSender,
+) -> impl Future<Output = Option<GreeterStatus>> + Send + 'static {
+    let (tx, rx) = oneshot::channel();
+    bus.send_to(CoreName::Status, StatusMessage::Get(tx));
+    async move { rx.await.ok() }
+}
+
+/// JSON view of the status, shared by the remote control API and the heartbeat.
+#[derive(Serialize)]
+pub struct StatusReport {
+    host: Option<String>,
+    wallpaper: String,
+    countdown_end: Option<String>,
+    contest_phase: Option<String>,
+    contest_api: String,
+    contest_api_polled: Option<String>,
+    dbus: String,
+    auth: String,
+    last_error: Option<String>,
+    last_error_time: Option<String>,
+}
+
+impl StatusReport {
+    pub fn new(status: &GreeterStatus) -> Self {
+        Self {
+            host: host::hostname(),
+            wallpaper: status.wallpaper.to_string(),
+            countdown_end: status
+                .countdown_end
+                .map(|dt| dt.to_rfc3339_opts(SecondsFormat::Secs, false)),
+            contest_phase: status.contest_phase.map(|phase| phase.to_string()),
+            contest_api: status.contest_api.to_string(),
+            contest_api_polled: status
+                .contest_api_polled
+                .map(|dt| dt.to_rfc3339_opts(SecondsFormat::Secs, false)),
+            dbus: status.dbus.to_string(),
+            auth: status.auth.to_string(),
+            last_error: status.last_error.as_ref().map(|e| e.message.clone()),
+            last_error_time: status
+                .last_error
+                .as_ref()
+                .map(|e| e.time.to_rfc3339_opts(SecondsFormat::Secs, false)),
+        }
+    }
+}
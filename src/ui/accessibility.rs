@@ -0,0 +1,176 @@
+use gtk4::{
+    AccessibleRole, Align, CssProvider, Label, STYLE_PROVIDER_PRIORITY_USER, Window,
+    accessible::Property, gdk::Display, prelude::*, style_context_add_provider_for_display,
+};
+use log::debug;
+
+use crate::i18n;
+use crate::ui::config::AccessibilityConfig;
+
+const HIGH_CONTRAST: &str = "high-contrast";
+const LARGE_TEXT: &str = "large-text";
+
+/// Applied with a class on the main window, so a theme can refine both modes.
+const ACCESSIBILITY_CSS: &str = "
+    label.announcer {
+        opacity: 0;
+    }
+
+    window.high-contrast .wallpaper-dim {
+        background-color: rgba(0, 0, 0, 0.8);
+    }
+
+    window.high-contrast label.phase-banner,
+    window.high-contrast .clock,
+    window.high-contrast .identity-panel,
+    window.high-contrast .message-overlay,
+    window.high-contrast .login-container {
+        background-color: black;
+        border: 3px solid yellow;
+        border-radius: 8px;
+    }
+
+    window.high-contrast label {
+        color: white;
+        text-shadow: none;
+    }
+
+    window.high-contrast label.countdown,
+    window.high-contrast label.error-label,
+    window.high-contrast label.login-lock-warning {
+        color: yellow;
+    }
+
+    window.high-contrast entry {
+        background-color: black;
+        color: white;
+        border: 2px solid white;
+    }
+
+    window.high-contrast entry:focus-within {
+        border-color: yellow;
+        outline: 3px solid yellow;
+    }
+
+    window.large-text .login-container {
+        min-width: 480px;
+    }
+
+    window.large-text entry,
+    window.large-text dropdown,
+    window.large-text .login-container label,
+    window.large-text button.login-button {
+        font-size: 28px;
+    }
+
+    window.large-text label.phase-banner,
+    window.large-text label.message-title {
+        font-size: 56px;
+    }
+
+    window.large-text label.clock-time {
+        font-size: 72px;
+    }
+
+    window.large-text label.clock-schedule,
+    window.large-text .identity-panel label,
+    window.large-text label.message-body,
+    window.large-text .staff-attention label.reason {
+        font-size: 36px;
+    }
+";
+
+/// High contrast and large text modes of the main window.
+#[derive(Clone)]
+pub struct Accessibility {
+    window: Window,
+    announcer: Announcer,
+}
+
+impl Accessibility {
+    pub fn new(conf: &AccessibilityConfig, window: &Window, announcer: Announcer) -> Self {
+        let css = CssProvider::new();
+        css.load_from_data(ACCESSIBILITY_CSS);
+        if let Some(display) = Display::default() {
+            // above the theme, a theme should not make the text unreadable again
+            style_context_add_provider_for_display(
+                &display,
+                &css,
+                STYLE_PROVIDER_PRIORITY_USER + 1,
+            );
+        }
+
+        if conf.high_contrast {
+            window.add_css_class(HIGH_CONTRAST);
+        }
+        if conf.large_text {
+            window.add_css_class(LARGE_TEXT);
+        }
+        Self {
+            window: window.clone(),
+            announcer,
+        }
+    }
+
+    pub fn toggle_high_contrast(&self) {
+        let on = self.toggle(HIGH_CONTRAST);
+        self.announcer.announce(&i18n::tr(if on {
+            "a11y-high-contrast-on"
+        } else {
+            "a11y-high-contrast-off"
+        }));
+    }
+
+    pub fn toggle_large_text(&self) {
+        let on = self.toggle(LARGE_TEXT);
+        self.announcer.announce(&i18n::tr(if on {
+            "a11y-large-text-on"
+        } else {
+            "a11y-large-text-off"
+        }));
+    }
+
+    /// Returns whether the mode is on now.
+    fn toggle(&self, class: &str) -> bool {
+        if self.window.has_css_class(class) {
+            self.window.remove_css_class(class);
+            false
+        } else {
+            self.window.add_css_class(class);
+            true
+        }
+    }
+}
+
+/// Sends text to screen readers. GTK 4.8 has no announcement API, but screen readers read an
+/// alert when it is shown, so an invisible alert label is shown again for every announcement.
+#[derive(Clone)]
+pub struct Announcer {
+    label: Label,
+}
+
+impl Announcer {
+    pub fn new() -> Self {
+        let label = Label::builder()
+            .accessible_role(AccessibleRole::Alert)
+            .halign(Align::Start)
+            .valign(Align::Start)
+            .can_target(false)
+            .visible(false)
+            .build();
+        label.add_css_class("announcer");
+        Self { label }
+    }
+
+    pub fn widget(&self) -> &Label {
+        &self.label
+    }
+
+    pub fn announce(&self, text: &str) {
+        debug!("[UI] announcing: {text}");
+        self.label.set_visible(false);
+        self.label.set_text(text);
+        self.label.update_property(&[Property::Label(text)]);
+        self.label.set_visible(true);
+    }
+}
@@ -1,32 +1,51 @@
 use std::{
-    io::Cursor,
+    cell::{Cell, RefCell},
+    collections::VecDeque,
+    fs,
     path::Path,
+    rc::Rc,
     sync::mpsc::{self, TryRecvError},
     time::Duration,
 };
 
 use anyhow::{Context, Result, anyhow};
-use gtk4::glib::{ControlFlow, timeout_add_local};
+use gtk4::glib::{
+    ControlFlow, SourceId, timeout_add_local, timeout_add_local_once, timeout_add_seconds_local,
+};
 use gtk4::{
-    CssProvider, Label, Overlay, Picture, STYLE_PROVIDER_PRIORITY_APPLICATION,
-    gdk::{Display, Texture},
+    Box as GTBox, ContentFit, CssProvider, DrawingArea, Label, Overlay, Picture,
+    STYLE_PROVIDER_PRIORITY_APPLICATION, Stack, StackTransitionType, Widget, cairo,
+    gdk::{Display, Texture, prelude::GdkCairoContextExt},
     gdk_pixbuf::Pixbuf,
     prelude::*,
     style_context_add_provider_for_display,
 };
-use log::debug;
+use log::{debug, warn};
+use types::WallpaperStatus;
+
+use crate::i18n;
+use crate::metrics::WALLPAPER_FAILURES;
+use crate::ui::config::{BackgroundFit, UiConfig};
+use crate::ui::image_check::{DecodedImage, ImageLimits, check_file, decode_bytes};
+use crate::ui::wallpaper_cache::{WallpaperCache, http_client, read_limited};
 
+#[derive(Clone)]
 pub struct Background {
     overlay: Overlay,
+    stack: Stack,
     _empty: Label,
     _invalid: Label,
+    fit: BackgroundFit,
+    cache: Option<WallpaperCache>,
+    limits: ImageLimits,
+    timeout: Duration,
+    report: Rc<dyn Fn(WallpaperStatus)>,
+    // bumped on every load so a slow download can't replace a newer wallpaper
+    generation: Rc<Cell<u64>>,
+    slideshow: Rc<RefCell<Option<SourceId>>>,
 }
 
 const BACKGROUND_CSS: &str = "
-    window { 
-        background-color: #808080; 
-    }
-
     label.watermark {
         font-size: 96px;
         color: rgba(255, 255, 255, 0.15);
@@ -34,33 +53,71 @@ const BACKGROUND_CSS: &str = "
     }
 ";
 
+const EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp", "svg", "avif"];
+
+/// Decoded remote wallpapers kept for windows of monitors plugged in later.
+const DECODED_CACHE_SIZE: usize = 3;
+
+thread_local! {
+    // every monitor gets a background, the display-wide style only needs installing once
+    static CSS_INSTALLED: Cell<bool> = const { Cell::new(false) };
+    static DECODED: RefCell<VecDeque<(String, Pixbuf)>> = const { RefCell::new(VecDeque::new()) };
+}
+
 impl Background {
-    pub fn new() -> Self {
+    /// `report` is called whenever the wallpaper status changes.
+    pub fn new(conf: &UiConfig, report: impl Fn(WallpaperStatus) + 'static) -> Self {
         let overlay = Overlay::new();
         overlay.set_hexpand(true);
         overlay.set_vexpand(true);
-        let _empty = Label::new(Some("No Wallpaper"));
+        let _empty = Label::new(Some(&i18n::tr("wallpaper-missing")));
         _empty.style_context().add_class("watermark");
 
-        let _invalid = Label::new(Some("Invalid Wallpaper"));
+        let _invalid = Label::new(Some(&i18n::tr("wallpaper-invalid")));
         _invalid.style_context().add_class("watermark");
 
-        let css = CssProvider::new();
-        css.load_from_data(BACKGROUND_CSS);
-
-        if let Some(display) = Display::default() {
-            style_context_add_provider_for_display(
-                &display,
-                &css,
-                STYLE_PROVIDER_PRIORITY_APPLICATION,
-            );
+        if !CSS_INSTALLED.replace(true) {
+            install_css(conf);
         }
 
-        overlay.set_child(Some(&_empty));
+        let stack = Stack::builder()
+            .transition_type(StackTransitionType::Crossfade)
+            .transition_duration(conf.background_crossfade)
+            .hexpand(true)
+            .vexpand(true)
+            .build();
+        stack.add_css_class("wallpaper");
+        stack.add_child(&_empty);
+        stack.add_child(&_invalid);
+        stack.set_visible_child(&_empty);
+
+        // darkens the wallpaper so the widgets on top of it stay legible
+        let dim = GTBox::builder()
+            .hexpand(true)
+            .vexpand(true)
+            .can_target(false)
+            .build();
+        dim.add_css_class("wallpaper-dim");
+
+        overlay.set_child(Some(&stack));
+        overlay.add_overlay(&dim);
         Self {
             overlay,
+            stack,
             _empty,
             _invalid,
+            fit: conf.background_fit,
+            cache: conf.background_cache.then(|| {
+                WallpaperCache::new(&conf.background_cache_dir, conf.background_cache_size)
+            }),
+            limits: ImageLimits {
+                max_bytes: conf.background_max_size * 1024 * 1024,
+                max_dimension: conf.background_max_dimension,
+            },
+            timeout: Duration::from_secs(conf.background_timeout),
+            report: Rc::new(report),
+            generation: Rc::new(Cell::new(0)),
+            slideshow: Rc::new(RefCell::new(None)),
         }
     }
 
@@ -69,55 +126,147 @@ impl Background {
     }
 
     pub fn set_empty(&self) {
-        self.overlay.set_child(Some(&self._empty));
+        self.stop_slideshow();
+        self.generation.set(self.generation.get() + 1);
+        self.show(&self._empty);
+        (self.report)(WallpaperStatus::Empty);
     }
 
     pub fn set_image(&self, path: &str) {
+        self.stop_slideshow();
+        self.load(path);
+    }
+
+    /// Rotates through `sources` every `interval` seconds. Directories are expanded to the
+    /// images they contain.
+    pub fn set_playlist(&self, sources: Vec<String>, interval: u64) {
+        self.stop_slideshow();
+
+        let playlist = expand_playlist(sources);
+        let Some(first) = playlist.first() else {
+            self.set_empty();
+            return;
+        };
+        self.load(first);
+        if playlist.len() < 2 || interval == 0 {
+            return;
+        }
+
+        let background = self.clone();
+        let index = Cell::new(0);
+        let id = timeout_add_seconds_local(interval as u32, move || {
+            index.set((index.get() + 1) % playlist.len());
+            background.load(&playlist[index.get()]);
+            ControlFlow::Continue
+        });
+        *self.slideshow.borrow_mut() = Some(id);
+    }
+
+    fn stop_slideshow(&self) {
+        if let Some(id) = self.slideshow.borrow_mut().take() {
+            id.remove();
+        }
+    }
+
+    fn load(&self, path: &str) {
+        // a window of a monitor plugged in later starts with what the others already show,
+        // later loads download again so a changed image at the same url is picked up
+        let first_load = self.generation.get() == 0;
+        self.generation.set(self.generation.get() + 1);
         if path.starts_with("http://") || path.starts_with("https://") {
+            if let Some(pixbuf) = decoded(path).filter(|_| first_load) {
+                debug!("[UI] reusing decoded wallpaper {path}");
+                let pic = picture_from_pixbuf(pixbuf, self.fit);
+                self.loaded(path, &pic);
+                return;
+            }
+            (self.report)(WallpaperStatus::Loading(path.to_string()));
             self.fetch_remote_image(path);
         } else {
-            match picture_from_filename(path) {
-                Ok(pic) => {
-                    self.overlay.set_child(Some(&pic));
-                }
-                Err(e) => {
-                    self.overlay.set_child(Some(&self._invalid));
-                    debug!("[UI] invalid file source ({path}): {e}");
-                    debug!("[UI] reset background to default background");
-                }
+            match picture_from_filename(path, self.fit, self.limits) {
+                Ok(pic) => self.loaded(path, &pic),
+                Err(e) => self.invalid(path, e),
             }
         }
     }
 
+    fn loaded(&self, source: &str, pic: &Widget) {
+        self.show(pic);
+        (self.report)(WallpaperStatus::Loaded(source.to_string()));
+    }
+
+    fn invalid(&self, source: &str, reason: anyhow::Error) {
+        self.show(&self._invalid);
+        WALLPAPER_FAILURES.inc(&[]);
+        warn!("[UI] invalid wallpaper ({source}): {reason:#}");
+        (self.report)(WallpaperStatus::Invalid {
+            source: source.to_string(),
+            reason: format!("{reason:#}"),
+        });
+    }
+
+    /// Crossfades to `child`, dropping the previous wallpaper once the transition is done.
+    fn show(&self, child: &impl IsA<Widget>) {
+        let previous = self.stack.visible_child();
+        if child.parent().is_none() {
+            self.stack.add_child(child);
+        }
+        self.stack.set_visible_child(child);
+
+        let Some(previous) = previous else {
+            return;
+        };
+        if &previous == child.upcast_ref::<Widget>()
+            || &previous == self._empty.upcast_ref::<Widget>()
+            || &previous == self._invalid.upcast_ref::<Widget>()
+        {
+            return;
+        }
+        let stack = self.stack.clone();
+        timeout_add_local_once(
+            Duration::from_millis(self.stack.transition_duration() as u64 + 100),
+            move || {
+                if stack.visible_child().as_ref() != Some(&previous) {
+                    stack.remove(&previous);
+                }
+            },
+        );
+    }
+
     fn fetch_remote_image(&self, url: &str) {
-        let (sender, receiver) = mpsc::channel::<std::result::Result<Vec<u8>, String>>();
+        let (sender, receiver) = mpsc::channel::<std::result::Result<DecodedImage, String>>();
         let url_string = url.to_string();
+        let cache = self.cache.clone();
+        let (timeout, limits) = (self.timeout, self.limits);
 
         std::thread::spawn(move || {
-            let res = fetch_image_bytes(&url_string).map_err(|e| e.to_string());
+            let res = fetch_image(&url_string, cache.as_ref(), timeout, limits)
+                .map_err(|e| format!("{e:#}"));
             let _ = sender.send(res);
         });
 
-        let overlay = self.overlay.clone();
-        let invalid = self._invalid.clone();
+        let background = self.clone();
+        let generation = self.generation.get();
+        let url = url.to_string();
         timeout_add_local(Duration::from_millis(50), move || {
-            match receiver.try_recv() {
-                Ok(Ok(bytes)) => match picture_from_bytes(bytes) {
-                    Ok(pic) => overlay.set_child(Some(&pic)),
-                    Err(e) => {
-                        overlay.set_child(Some(&invalid));
-                        debug!("[UI] failed to decode remote image: {e}");
-                    }
-                },
-                Ok(Err(e)) => {
-                    overlay.set_child(Some(&invalid));
-                    debug!("[UI] failed to fetch remote image: {e}");
-                }
+            let result = match receiver.try_recv() {
                 Err(TryRecvError::Empty) => return ControlFlow::Continue,
-                Err(TryRecvError::Disconnected) => {
-                    overlay.set_child(Some(&invalid));
-                    debug!("[UI] remote fetch task disconnected");
+                result => result,
+            };
+            if background.generation.get() != generation {
+                debug!("[UI] discarding outdated remote image");
+                return ControlFlow::Break;
+            }
+
+            match result {
+                Ok(Ok(image)) => {
+                    let pixbuf = image.pixbuf();
+                    remember_decoded(&url, &pixbuf);
+                    let pic = picture_from_pixbuf(pixbuf, background.fit);
+                    background.loaded(&url, &pic);
                 }
+                Ok(Err(e)) => background.invalid(&url, anyhow!(e)),
+                Err(_) => background.invalid(&url, anyhow!("remote fetch task disconnected")),
             }
 
             ControlFlow::Break
@@ -125,47 +274,167 @@ impl Background {
     }
 }
 
-fn picture_from_filename(source: &str) -> Result<Picture> {
-    let p = Path::new(source);
+fn has_image_extension(p: &Path) -> bool {
+    p.extension()
+        .and_then(|e| e.to_str())
+        .map(|ext| EXTENSIONS.contains(&ext.to_lowercase().as_str()))
+        .unwrap_or(false)
+}
 
-    const EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "webp"];
+/// Replaces directories in the playlist by the images they contain, in name order.
+fn expand_playlist(sources: Vec<String>) -> Vec<String> {
+    let mut playlist = Vec::new();
+    for source in sources {
+        let p = Path::new(&source);
+        if !p.is_dir() {
+            playlist.push(source);
+            continue;
+        }
 
-    let image_is_valid = p.is_file()
-        && p.extension()
-            .and_then(|e| e.to_str())
-            .map(|ext| EXTENSIONS.contains(&ext.to_lowercase().as_str()))
-            .unwrap_or(false);
+        match fs::read_dir(p) {
+            Ok(entries) => {
+                let mut images = entries
+                    .filter_map(|entry| entry.ok().map(|e| e.path()))
+                    .filter(|path| path.is_file() && has_image_extension(path))
+                    .filter_map(|path| path.to_str().map(str::to_string))
+                    .collect::<Vec<_>>();
+                images.sort();
+                playlist.extend(images);
+            }
+            Err(e) => debug!("[UI] failed to read wallpaper directory ({source}): {e}"),
+        }
+    }
+    playlist
+}
 
-    if image_is_valid {
-        let pic = Picture::for_filename(p);
-        pic.set_vexpand(true);
-        pic.set_hexpand(true);
-        Ok(pic)
-    } else {
-        Err(anyhow!("invalid filepath {source}"))
+fn picture_from_filename(source: &str, fit: BackgroundFit, limits: ImageLimits) -> Result<Widget> {
+    let p = Path::new(source);
+    if !p.is_file() {
+        return Err(anyhow!("invalid filepath {source}"));
     }
+    check_file(p, limits)?;
+
+    let Some(content_fit) = content_fit(fit) else {
+        let pixbuf = Pixbuf::from_file(p)
+            .map_err(|e| anyhow::anyhow!(e))
+            .context("Failed to decode image file")?;
+        return Ok(unscaled_picture(pixbuf, fit == BackgroundFit::Tile).upcast());
+    };
+
+    let pic = Picture::for_filename(p);
+    pic.set_vexpand(true);
+    pic.set_hexpand(true);
+    pic.set_content_fit(content_fit);
+    Ok(pic.upcast())
 }
 
-fn fetch_image_bytes(url: &str) -> Result<Vec<u8>> {
-    let response = reqwest::blocking::get(url).context("Failed to connect to the URL")?;
+/// Downloads and decodes a remote wallpaper, meant to run off the main thread.
+fn fetch_image(
+    url: &str,
+    cache: Option<&WallpaperCache>,
+    timeout: Duration,
+    limits: ImageLimits,
+) -> Result<DecodedImage> {
+    let client = http_client(timeout)?;
+    if let Some(cache) = cache {
+        return cache.fetch(&client, url, limits);
+    }
 
-    let bytes = response.bytes().context("Failed to download image bytes")?;
+    let response = client
+        .get(url)
+        .send()
+        .and_then(|r| r.error_for_status())
+        .context("Failed to connect to the URL")?;
 
-    Ok(bytes.to_vec())
+    decode_bytes(&read_limited(response, limits.max_bytes)?, limits)
 }
 
-fn picture_from_bytes(bytes: Vec<u8>) -> Result<Picture> {
-    // 2. Decode the data
-    let cursor = Cursor::new(bytes);
-    let pixbuf = Pixbuf::from_read(cursor)
-        .map_err(|e| anyhow::anyhow!(e))
-        .context("Failed to decode image data (invalid format?)")?;
+fn picture_from_pixbuf(pixbuf: Pixbuf, fit: BackgroundFit) -> Widget {
+    let Some(content_fit) = content_fit(fit) else {
+        return unscaled_picture(pixbuf, fit == BackgroundFit::Tile).upcast();
+    };
 
     let texture = Texture::for_pixbuf(&pixbuf);
 
     let picture = Picture::for_paintable(&texture);
     picture.set_vexpand(true);
     picture.set_hexpand(true);
+    picture.set_content_fit(content_fit);
 
-    Ok(picture)
+    picture.upcast()
+}
+
+fn decoded(url: &str) -> Option<Pixbuf> {
+    DECODED.with_borrow(|decoded| {
+        decoded
+            .iter()
+            .find(|(cached, _)| cached == url)
+            .map(|(_, pixbuf)| pixbuf.clone())
+    })
+}
+
+fn remember_decoded(url: &str, pixbuf: &Pixbuf) {
+    DECODED.with_borrow_mut(|decoded| {
+        decoded.retain(|(cached, _)| cached != url);
+        if decoded.len() == DECODED_CACHE_SIZE {
+            decoded.pop_front();
+        }
+        decoded.push_back((url.to_string(), pixbuf.clone()));
+    });
+}
+
+fn install_css(conf: &UiConfig) {
+    let css = CssProvider::new();
+    css.load_from_data(&format!(
+        "
+        window {{
+            background-color: {};
+        }}
+        .wallpaper {{
+            filter: blur({}px);
+        }}
+        .wallpaper-dim {{
+            background-color: rgba(0, 0, 0, {});
+        }}
+        {BACKGROUND_CSS}
+    ",
+        conf.background_color,
+        conf.background_blur,
+        conf.background_dim.clamp(0.0, 1.0),
+    ));
+
+    if let Some(display) = Display::default() {
+        style_context_add_provider_for_display(&display, &css, STYLE_PROVIDER_PRIORITY_APPLICATION);
+    }
+}
+
+/// How `Picture` scales the wallpaper, `None` for the original size placements it can't do.
+fn content_fit(fit: BackgroundFit) -> Option<ContentFit> {
+    match fit {
+        BackgroundFit::Cover => Some(ContentFit::Cover),
+        BackgroundFit::Contain => Some(ContentFit::Contain),
+        BackgroundFit::Fill => Some(ContentFit::Fill),
+        BackgroundFit::Center | BackgroundFit::Tile => None,
+    }
+}
+
+/// `Picture` always scales a larger image down and can't repeat it, so original size
+/// wallpapers are drawn with cairo: centred and cropped by the screen edges, or tiled from the
+/// top left corner with a repeating pattern.
+fn unscaled_picture(pixbuf: Pixbuf, tile: bool) -> DrawingArea {
+    let area = DrawingArea::builder().hexpand(true).vexpand(true).build();
+    area.set_draw_func(move |_, cr, width, height| {
+        if tile {
+            cr.set_source_pixbuf(&pixbuf, 0.0, 0.0);
+            cr.source().set_extend(cairo::Extend::Repeat);
+        } else {
+            let x = (width - pixbuf.width()) / 2;
+            let y = (height - pixbuf.height()) / 2;
+            cr.set_source_pixbuf(&pixbuf, x as f64, y as f64);
+        }
+        if let Err(e) = cr.paint() {
+            debug!("[UI] failed to draw wallpaper: {e}");
+        }
+    });
+    area
 }
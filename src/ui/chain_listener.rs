@@ -1,28 +1,180 @@
-use std::{cell::RefCell, rc::Rc};
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    time::{Duration, Instant},
+};
 
-use gtk4::{EventControllerKey, Window, prelude::WidgetExt};
+use anyhow::{Result, anyhow};
+use gtk4::{
+    EventControllerKey, Window,
+    gdk::{Key, ModifierType},
+    prelude::WidgetExt,
+};
 
-pub fn register_chain_listener(
+/// Modifiers that are significant for key combinations.
+const MODIFIERS: ModifierType = ModifierType::CONTROL_MASK
+    .union(ModifierType::ALT_MASK)
+    .union(ModifierType::SHIFT_MASK)
+    .union(ModifierType::SUPER_MASK);
+
+/// One step of a key sequence.
+#[derive(Clone, Debug, PartialEq)]
+enum Step {
+    /// A typed character, modifiers are ignored.
+    Char(char),
+    /// A key pressed together with exactly these modifiers.
+    Combo(Key, ModifierType),
+}
+
+impl Step {
+    fn matches(&self, key: Key, modifiers: ModifierType) -> bool {
+        match self {
+            Step::Char(ch) => key.to_unicode() == Some(*ch),
+            Step::Combo(expected, expected_modifiers) => match symbol(key) {
+                // Shift picks the symbol on most layouts and the keypad has its own keys, so
+                // `Ctrl+Alt+Plus` matches both `Ctrl+Alt+Shift+=` and the keypad plus
+                Some(ch) => {
+                    let significant = MODIFIERS.difference(ModifierType::SHIFT_MASK);
+                    expected.to_unicode() == Some(ch)
+                        && modifiers & significant == *expected_modifiers & significant
+                }
+                None => key.to_lower() == *expected && modifiers & MODIFIERS == *expected_modifiers,
+            },
+        }
+    }
+}
+
+/// Character of a punctuation or symbol key, letters, digits and function keys have none.
+fn symbol(key: Key) -> Option<char> {
+    key.to_unicode()
+        .filter(|ch| !ch.is_alphanumeric() && !ch.is_whitespace() && !ch.is_control())
+}
+
+/// A key sequence bound to an action.
+pub struct Chain<A> {
+    steps: Vec<Step>,
+    action: A,
+}
+
+impl<A> Chain<A> {
+    /// Parses `spec`, either a plain string typed key by key or space separated combinations
+    /// like `Ctrl+Alt+L`.
+    pub fn parse(spec: &str, action: A) -> Result<Self> {
+        let is_combo = spec.split_whitespace().any(|token| {
+            token
+                .split_once('+')
+                .is_some_and(|(modifier, _)| parse_modifier(modifier).is_some())
+        });
+        let steps = if is_combo {
+            spec.split_whitespace()
+                .map(parse_combo)
+                .collect::<Result<Vec<_>>>()?
+        } else {
+            spec.chars().map(Step::Char).collect()
+        };
+        if steps.is_empty() {
+            return Err(anyhow!("empty key sequence"));
+        }
+        Ok(Self { steps, action })
+    }
+}
+
+fn parse_combo(token: &str) -> Result<Step> {
+    let mut parts = token.split('+').collect::<Vec<_>>();
+    let key_name = parts.pop().filter(|k| !k.is_empty());
+    let key_name = key_name.ok_or(anyhow!("missing key in {token}"))?;
+
+    let mut modifiers = ModifierType::empty();
+    for part in parts {
+        modifiers |= parse_modifier(part).ok_or(anyhow!("unknown modifier {part} in {token}"))?;
+    }
+
+    // letters and digits are their own key names, other keys use GDK names like `F12` or `comma`
+    let key = Key::from_name(key_name.to_ascii_lowercase())
+        .or_else(|| Key::from_name(key_name))
+        .ok_or(anyhow!("unknown key {key_name} in {token}"))?;
+    Ok(Step::Combo(key.to_lower(), modifiers))
+}
+
+fn parse_modifier(name: &str) -> Option<ModifierType> {
+    match name.to_lowercase().as_str() {
+        "ctrl" | "control" => Some(ModifierType::CONTROL_MASK),
+        "alt" => Some(ModifierType::ALT_MASK),
+        "shift" => Some(ModifierType::SHIFT_MASK),
+        "super" => Some(ModifierType::SUPER_MASK),
+        _ => None,
+    }
+}
+
+fn is_modifier_key(key: Key) -> bool {
+    matches!(
+        key,
+        Key::Control_L
+            | Key::Control_R
+            | Key::Alt_L
+            | Key::Alt_R
+            | Key::Shift_L
+            | Key::Shift_R
+            | Key::Super_L
+            | Key::Super_R
+            | Key::Meta_L
+            | Key::Meta_R
+            | Key::ISO_Level3_Shift
+    )
+}
+
+/// Calls `callback` with the action of every chain that was just completed. Keys further than
+/// `timeout` apart don't count as one sequence.
+pub fn register_chain_listener<A: Copy + 'static>(
     window: &Window,
-    sequence: Vec<char>,
-    callback: impl Fn() + 'static,
+    chains: Vec<Chain<A>>,
+    timeout: Option<Duration>,
+    callback: impl Fn(A) + 'static,
 ) {
-    let buffer_size = sequence.len();
-    let key_buffer: Rc<RefCell<Vec<char>>> = Rc::new(RefCell::new(Vec::with_capacity(buffer_size)));
+    let Some(buffer_size) = chains.iter().map(|c| c.steps.len()).max() else {
+        return;
+    };
+    let key_buffer: Rc<RefCell<Vec<(Key, ModifierType)>>> =
+        Rc::new(RefCell::new(Vec::with_capacity(buffer_size)));
+    let last_press = Rc::new(RefCell::new(Instant::now()));
 
     let event_controller = EventControllerKey::new();
 
-    event_controller.connect_key_pressed(move |_, key, _, _| {
-        if let Some(ch) = key.to_unicode() {
-            let mut buf = key_buffer.borrow_mut();
-            if buf.len() == buffer_size {
-                buf.remove(0);
-            }
-            buf.push(ch);
-
-            if *buf == sequence {
-                callback();
-            }
+    event_controller.connect_key_pressed(move |_, key, _, modifiers| {
+        // modifiers are only relevant together with the key they modify
+        if is_modifier_key(key) {
+            return gtk4::glib::Propagation::Proceed;
+        }
+
+        let mut buf = key_buffer.borrow_mut();
+        let now = Instant::now();
+        let elapsed = now - last_press.replace(now);
+        if timeout.is_some_and(|timeout| elapsed > timeout) {
+            buf.clear();
+        }
+        if buf.len() == buffer_size {
+            buf.remove(0);
+        }
+        buf.push((key, modifiers));
+
+        let completed = chains
+            .iter()
+            .filter(|chain| {
+                buf.len() >= chain.steps.len()
+                    && buf[buf.len() - chain.steps.len()..]
+                        .iter()
+                        .zip(&chain.steps)
+                        .all(|((key, modifiers), step)| step.matches(*key, *modifiers))
+            })
+            .map(|chain| chain.action)
+            .collect::<Vec<_>>();
+        if !completed.is_empty() {
+            buf.clear();
+        }
+        drop(buf);
+
+        for action in completed {
+            callback(action);
         }
 
         gtk4::glib::Propagation::Proceed
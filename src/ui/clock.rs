@@ -0,0 +1,181 @@
+use std::{cell::RefCell, rc::Rc};
+
+use chrono::{
+    DateTime, Local, TimeDelta,
+    format::{Item, StrftimeItems},
+};
+use gtk4::{
+    AccessibleRole, Box as GTBox, CssProvider, Label, Orientation,
+    STYLE_PROVIDER_PRIORITY_APPLICATION,
+    accessible::Property,
+    gdk::Display,
+    glib::{ControlFlow, timeout_add_seconds_local},
+    prelude::*,
+    style_context_add_provider_for_display,
+};
+use log::warn;
+use types::ContestInfo;
+
+use crate::contest_api::parse_reltime;
+use crate::i18n;
+use crate::ui::config::{ClockConfig, default_clock_format};
+
+/// Wall clock with an optional schedule line below it.
+pub struct Clock {
+    container: GTBox,
+    schedule: Label,
+    show_schedule: bool,
+    state: Rc<RefCell<Schedule>>,
+}
+
+struct Schedule {
+    name: Option<String>,
+    start_time: Option<DateTime<Local>>,
+    duration: Option<TimeDelta>,
+}
+
+const CLOCK_CSS: &str = "
+    .clock {
+        margin: 20px;
+    }
+
+    .clock label.clock-time {
+        font-size: 48px;
+        font-weight: bold;
+        color: white;
+    }
+
+    .clock label.clock-schedule {
+        font-size: 20px;
+        color: white;
+    }
+";
+
+impl Clock {
+    pub fn new(conf: &ClockConfig, start_time: Option<DateTime<Local>>) -> Self {
+        let (halign, valign) = conf.position.align();
+        let container = GTBox::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(4)
+            .halign(halign)
+            .valign(valign)
+            .visible(conf.enabled)
+            .accessible_role(AccessibleRole::Group)
+            .build();
+        container.update_property(&[Property::Label(&i18n::tr("clock-name"))]);
+        container.add_css_class("clock");
+
+        let time = Label::new(None);
+        time.add_css_class("clock-time");
+        let schedule = Label::builder().visible(false).build();
+        schedule.add_css_class("clock-schedule");
+        container.append(&time);
+        container.append(&schedule);
+
+        let css = CssProvider::new();
+        css.load_from_data(CLOCK_CSS);
+        if let Some(display) = Display::default() {
+            style_context_add_provider_for_display(
+                &display,
+                &css,
+                STYLE_PROVIDER_PRIORITY_APPLICATION,
+            );
+        }
+
+        let duration =
+            conf.contest_duration
+                .as_deref()
+                .and_then(|repr| match parse_reltime(repr) {
+                    Ok(duration) => Some(duration),
+                    Err(e) => {
+                        warn!("[UI] invalid contest_duration: {e:#}");
+                        None
+                    }
+                });
+
+        let clock = Self {
+            container,
+            schedule,
+            show_schedule: conf.show_schedule,
+            state: Rc::new(RefCell::new(Schedule {
+                name: conf.contest_name.clone(),
+                start_time,
+                duration,
+            })),
+        };
+
+        if conf.enabled {
+            let format = if is_valid_format(&conf.format) {
+                conf.format.clone()
+            } else {
+                warn!(
+                    "[UI] invalid clock format {:?}, using the default",
+                    conf.format
+                );
+                default_clock_format()
+            };
+            time.set_text(&Local::now().format(&format).to_string());
+            timeout_add_seconds_local(1, move || {
+                time.set_text(&Local::now().format(&format).to_string());
+                ControlFlow::Continue
+            });
+        }
+        clock.update_schedule();
+
+        clock
+    }
+
+    pub fn widget(&self) -> &GTBox {
+        &self.container
+    }
+
+    pub fn set_start_time(&self, start_time: Option<DateTime<Local>>) {
+        if start_time.is_some() {
+            self.state.borrow_mut().start_time = start_time;
+            self.update_schedule();
+        }
+    }
+
+    pub fn set_contest_info(&self, info: ContestInfo) {
+        let mut state = self.state.borrow_mut();
+        state.name = info.name.or(state.name.take());
+        state.start_time = Some(info.start_time);
+        state.duration = info.duration.or(state.duration);
+        drop(state);
+        self.update_schedule();
+    }
+
+    fn update_schedule(&self) {
+        if !self.show_schedule {
+            return;
+        }
+        let state = self.state.borrow();
+        let mut parts = Vec::new();
+        if let Some(name) = &state.name {
+            parts.push(name.clone());
+        }
+        if let Some(start_time) = state.start_time {
+            let time = start_time.format("%H:%M").to_string();
+            parts.push(i18n::tr_args("clock-starts-at", &[("time", time.into())]));
+        }
+        if let Some(duration) = state.duration {
+            let duration = format!(
+                "{}:{:02}",
+                duration.num_hours(),
+                duration.num_minutes() % 60
+            );
+            parts.push(i18n::tr_args(
+                "clock-duration",
+                &[("duration", duration.into())],
+            ));
+        }
+
+        self.schedule.set_text(&parts.join(" · "));
+        self.schedule.set_visible(!parts.is_empty());
+    }
+}
+
+/// Formatting a date with an invalid specifier panics, so the format is checked up front.
+fn is_valid_format(format: &str) -> bool {
+    !StrftimeItems::new(format).any(|item| matches!(item, Item::Error))
+}
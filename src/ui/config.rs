@@ -1,5 +1,6 @@
 use chrono::TimeZone;
 use chrono::{DateTime, FixedOffset, Local, NaiveDateTime, Offset};
+use gtk4::Align;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -9,9 +10,74 @@ pub struct UiConfig {
     #[serde(default = "default_chain")]
     pub(crate) chain: String,
 
+    /// Additional key sequences bound to actions, e.g.
+    /// `chains = [{ keys = "Ctrl+Alt+D", action = "diagnostics" }]`. A sequence is either a plain
+    /// string typed key by key, or space separated key combinations such as `Ctrl+Alt+L` or
+    /// `Ctrl+D Ctrl+G`. Actions: `toggle_login`, `diagnostics`, `auto_login`, `reload_config`,
+    /// `reboot`, `high_contrast` and `large_text`.
+    #[serde(default)]
+    pub(crate) chains: Vec<ChainBinding>,
+
+    /// Maximum time in milliseconds between two keys of a sequence. `0` disables the limit.
+    #[serde(default = "default_chain_timeout")]
+    pub(crate) chain_timeout: u64,
+
     /// File path or URL for the background image.
     pub(crate) background_source: Option<String>,
 
+    /// Wallpapers to rotate through (file paths, URLs or directories). Takes precedence over
+    /// `background_source` when not empty.
+    #[serde(default)]
+    pub(crate) background_playlist: Vec<String>,
+
+    /// Seconds between wallpaper changes when a playlist is set.
+    #[serde(default = "default_background_interval")]
+    pub(crate) background_interval: u64,
+
+    /// Duration of the crossfade between wallpapers in milliseconds.
+    #[serde(default = "default_background_crossfade")]
+    pub(crate) background_crossfade: u32,
+
+    /// How the wallpaper is scaled to the screen.
+    #[serde(default = "default_background_fit")]
+    pub(crate) background_fit: BackgroundFit,
+
+    /// Color shown around the wallpaper when it doesn't cover the screen (CSS color value).
+    #[serde(default = "default_background_color")]
+    pub(crate) background_color: String,
+
+    /// Blur radius applied to the wallpaper in pixels.
+    #[serde(default)]
+    pub(crate) background_blur: u32,
+
+    /// Darkens the wallpaper, from `0.0` (unchanged) to `1.0` (black).
+    #[serde(default)]
+    pub(crate) background_dim: f64,
+
+    /// Keep a copy of remote wallpapers on disk, used when the network is unavailable.
+    #[serde(default = "default_background_cache")]
+    pub(crate) background_cache: bool,
+
+    /// Directory for cached remote wallpapers.
+    #[serde(default = "default_background_cache_dir")]
+    pub(crate) background_cache_dir: String,
+
+    /// Maximum size of the wallpaper cache in megabytes.
+    #[serde(default = "default_background_cache_size")]
+    pub(crate) background_cache_size: u64,
+
+    /// Largest wallpaper accepted, in megabytes, for both local files and downloads.
+    #[serde(default = "default_background_max_size")]
+    pub(crate) background_max_size: u64,
+
+    /// Largest accepted wallpaper width or height in pixels.
+    #[serde(default = "default_background_max_dimension")]
+    pub(crate) background_max_dimension: i32,
+
+    /// Time limit in seconds for downloading a remote wallpaper.
+    #[serde(default = "default_background_timeout")]
+    pub(crate) background_timeout: u64,
+
     /// Contest start time. Accepts RFC3339 or `YYYY-MM-DD hh:mm:ss` (interpreted as local time).
     #[serde(default, deserialize_with = "deserialize_end_time")]
     pub(crate) countdown_end_time: Option<DateTime<FixedOffset>>,
@@ -27,17 +93,376 @@ pub struct UiConfig {
     /// Color for the countdown label (CSS color value).
     #[serde(default = "default_countdown_label_color")]
     pub(crate) countdown_label_color: String,
+
+    /// Theme CSS file, or a directory containing `theme.css` and the assets it references.
+    /// Loaded on top of the built-in styles, see `THEMING.md` for the available CSS classes.
+    pub(crate) theme: Option<String>,
+
+    /// Reload the theme when a file in its directory changes.
+    #[serde(default = "default_theme_reload")]
+    pub(crate) theme_reload: bool,
+
+    /// Team/seat identity panel.
+    #[serde(default)]
+    pub(crate) identity: IdentityConfig,
+
+    /// Wall clock and contest schedule widget.
+    #[serde(default)]
+    pub(crate) clock: ClockConfig,
+
+    /// Multi-monitor layout.
+    #[serde(default)]
+    pub(crate) monitors: MonitorsConfig,
+
+    /// Placement of the greeter widgets and additional images and text blocks.
+    #[serde(default)]
+    pub(crate) layout: LayoutConfig,
+
+    /// High contrast, large text and screen reader announcements.
+    #[serde(default)]
+    pub(crate) accessibility: AccessibilityConfig,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+pub struct IdentityConfig {
+    /// Show the identity panel with team name, seat, hostname and IP address.
+    #[serde(default)]
+    pub(crate) enabled: bool,
+
+    /// Team name shown on every machine, unless overridden per host or by the contest API.
+    pub(crate) team_name: Option<String>,
+
+    /// Seat or location shown on every machine, unless overridden per host.
+    pub(crate) seat: Option<String>,
+
+    /// Per host overrides, e.g. `hosts = [{ hostname = "pc01", team_name = "Team A", seat = "A1" }]`.
+    #[serde(default)]
+    pub(crate) hosts: Vec<HostIdentity>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct HostIdentity {
+    /// Hostname this entry applies to.
+    pub(crate) hostname: String,
+
+    /// Team name for this host.
+    pub(crate) team_name: Option<String>,
+
+    /// Seat or location of this host.
+    pub(crate) seat: Option<String>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct MonitorsConfig {
+    /// Monitor hosting the countdown and login panel, by connector name (e.g. `DP-1`) or index.
+    /// Defaults to the first monitor.
+    pub(crate) primary: Option<String>,
+
+    /// Cover the other monitors with a window showing only the wallpaper.
+    #[serde(default = "default_all_monitors")]
+    pub(crate) all_monitors: bool,
+
+    /// Per monitor wallpapers, e.g. `wallpapers = [{ monitor = "HDMI-1", source = "/path/to/image.png" }]`.
+    /// Other monitors follow the main wallpaper.
+    #[serde(default)]
+    pub(crate) wallpapers: Vec<MonitorWallpaper>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct MonitorWallpaper {
+    /// Connector name or index of the monitor.
+    pub(crate) monitor: String,
+
+    /// File path, url or directory (shown as a slideshow) for this monitor.
+    pub(crate) source: String,
+}
+
+impl Default for MonitorsConfig {
+    fn default() -> Self {
+        Self {
+            primary: None,
+            all_monitors: default_all_monitors(),
+            wallpapers: Vec::new(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ClockConfig {
+    /// Show the wall clock.
+    #[serde(default)]
+    pub(crate) enabled: bool,
+
+    /// Clock format (strftime syntax).
+    #[serde(default = "default_clock_format")]
+    pub(crate) format: String,
+
+    /// Show a schedule line with contest name, start time and duration below the clock.
+    #[serde(default = "default_show_schedule")]
+    pub(crate) show_schedule: bool,
+
+    /// Contest name for the schedule line, overridden by the contest API.
+    pub(crate) contest_name: Option<String>,
+
+    /// Contest duration for the schedule line (`h:mm:ss`), overridden by the contest API.
+    pub(crate) contest_duration: Option<String>,
+
+    /// Where the clock is placed on screen.
+    #[serde(default = "default_clock_position")]
+    pub(crate) position: Anchor,
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            format: default_clock_format(),
+            show_schedule: default_show_schedule(),
+            contest_name: None,
+            contest_duration: None,
+            position: default_clock_position(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct AccessibilityConfig {
+    /// Start in high contrast mode, toggled with `high_contrast_keys` or the `high_contrast`
+    /// chain action.
+    #[serde(default)]
+    pub(crate) high_contrast: bool,
+
+    /// Key sequence that toggles high contrast mode, empty to disable it.
+    #[serde(default = "default_high_contrast_keys")]
+    pub(crate) high_contrast_keys: String,
+
+    /// Start with large text, toggled with `large_text_keys` or the `large_text` chain action.
+    #[serde(default)]
+    pub(crate) large_text: bool,
+
+    /// Key sequence that toggles large text, empty to disable it.
+    #[serde(default = "default_large_text_keys")]
+    pub(crate) large_text_keys: String,
+
+    /// Seconds before the contest start at which screen readers announce the remaining time,
+    /// `0` announces the start itself.
+    #[serde(default = "default_announce_at")]
+    pub(crate) announce_at: Vec<u64>,
+}
+
+impl Default for AccessibilityConfig {
+    fn default() -> Self {
+        Self {
+            high_contrast: false,
+            high_contrast_keys: default_high_contrast_keys(),
+            large_text: false,
+            large_text_keys: default_large_text_keys(),
+            announce_at: default_announce_at(),
+        }
+    }
+}
+
+/// Scaling mode of the wallpaper.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum BackgroundFit {
+    /// Fill the screen, cropping the image where needed.
+    Cover,
+    /// Show the whole image, letterboxed with `background_color`.
+    Contain,
+    /// Stretch the image to the screen size.
+    Fill,
+    /// Original size in the middle of the screen, cropped when larger than the screen.
+    Center,
+    /// Repeat the image at its original size.
+    Tile,
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Serialize, JsonSchema)]
+pub struct LayoutConfig {
+    /// Countdown label, centred by default.
+    #[serde(default)]
+    pub(crate) countdown: Placement,
+
+    /// Dot shown while the contest start time is known, top right by default.
+    #[serde(default)]
+    pub(crate) status_dot: Placement,
+
+    /// Login panel, centred by default. Hiding it disables manual login.
+    #[serde(default)]
+    pub(crate) login: Placement,
+
+    /// Contest phase banner, at the top by default.
+    #[serde(default)]
+    pub(crate) phase_banner: Placement,
+
+    /// Clock, placed at `clock.position` by default.
+    #[serde(default)]
+    pub(crate) clock: Placement,
+
+    /// Identity panel, bottom left by default.
+    #[serde(default)]
+    pub(crate) identity: Placement,
+
+    /// Announcements, at the bottom by default.
+    #[serde(default)]
+    pub(crate) message: Placement,
+
+    /// Images such as logos, e.g. `images = [{ source = "/etc/lightdm/logo.png", anchor = "top_left", height = 120 }]`.
+    #[serde(default)]
+    pub(crate) images: Vec<LayoutImage>,
+
+    /// Text blocks, e.g. `texts = [{ text = "Welcome to the finals", anchor = "top", class = "title" }]`.
+    #[serde(default)]
+    pub(crate) texts: Vec<LayoutText>,
+}
+
+/// Position and size of a widget; unset values keep the widget's defaults.
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct Placement {
+    /// Screen position.
+    pub(crate) anchor: Option<Anchor>,
+
+    /// Margin in pixels on every side.
+    pub(crate) margin: Option<i32>,
+
+    /// Minimum width in pixels.
+    pub(crate) width: Option<i32>,
+
+    /// Minimum height in pixels.
+    pub(crate) height: Option<i32>,
+
+    /// Show the widget.
+    #[serde(default = "default_visible")]
+    pub(crate) visible: bool,
+}
+
+impl Default for Placement {
+    fn default() -> Self {
+        Self {
+            anchor: None,
+            margin: None,
+            width: None,
+            height: None,
+            visible: default_visible(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct LayoutImage {
+    /// Image file path.
+    pub(crate) source: String,
+
+    #[serde(flatten)]
+    pub(crate) placement: Placement,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct LayoutText {
+    /// Text to show.
+    pub(crate) text: String,
+
+    /// Extra CSS class for theming.
+    pub(crate) class: Option<String>,
+
+    #[serde(flatten)]
+    pub(crate) placement: Placement,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize, JsonSchema)]
+pub struct ChainBinding {
+    /// Key sequence.
+    pub(crate) keys: String,
+
+    /// Action triggered by the sequence.
+    pub(crate) action: ChainAction,
+}
+
+/// Action bound to a key sequence.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ChainAction {
+    /// Show or hide the login panel.
+    ToggleLogin,
+    /// Show or hide the diagnostics panel.
+    Diagnostics,
+    /// Log in with the configured automatic login credentials.
+    AutoLogin,
+    /// Re-read the configuration file. The greeter settings, log level, wallpaper and countdown
+    /// end time are applied right away, other changes need a greeter restart.
+    ReloadConfig,
+    /// Reboot the machine, once the sequence is repeated within 5 seconds.
+    Reboot,
+    /// Switch high contrast mode on or off.
+    HighContrast,
+    /// Switch large text on or off.
+    LargeText,
+}
+
+/// Screen position of a widget.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum Anchor {
+    TopLeft,
+    Top,
+    TopRight,
+    Left,
+    Center,
+    Right,
+    BottomLeft,
+    Bottom,
+    BottomRight,
+}
+
+impl Anchor {
+    /// Horizontal and vertical alignment for this anchor.
+    pub(crate) fn align(self) -> (Align, Align) {
+        match self {
+            Anchor::TopLeft => (Align::Start, Align::Start),
+            Anchor::Top => (Align::Center, Align::Start),
+            Anchor::TopRight => (Align::End, Align::Start),
+            Anchor::Left => (Align::Start, Align::Center),
+            Anchor::Center => (Align::Center, Align::Center),
+            Anchor::Right => (Align::End, Align::Center),
+            Anchor::BottomLeft => (Align::Start, Align::End),
+            Anchor::Bottom => (Align::Center, Align::End),
+            Anchor::BottomRight => (Align::End, Align::End),
+        }
+    }
 }
 
 impl Default for UiConfig {
     fn default() -> Self {
         Self {
             chain: default_chain(),
+            chains: Vec::new(),
+            chain_timeout: default_chain_timeout(),
             background_source: None,
+            background_playlist: Vec::new(),
+            background_interval: default_background_interval(),
+            background_crossfade: default_background_crossfade(),
+            background_fit: default_background_fit(),
+            background_color: default_background_color(),
+            background_blur: 0,
+            background_dim: 0.0,
+            background_cache: default_background_cache(),
+            background_cache_dir: default_background_cache_dir(),
+            background_cache_size: default_background_cache_size(),
+            background_max_size: default_background_max_size(),
+            background_max_dimension: default_background_max_dimension(),
+            background_timeout: default_background_timeout(),
             countdown_end_time: None,
             countdown_from: default_count_from(),
             countdown_end_login: default_count_end_login(),
             countdown_label_color: default_countdown_label_color(),
+            theme: None,
+            theme_reload: default_theme_reload(),
+            identity: IdentityConfig::default(),
+            clock: ClockConfig::default(),
+            monitors: MonitorsConfig::default(),
+            layout: LayoutConfig::default(),
+            accessibility: AccessibilityConfig::default(),
         }
     }
 }
@@ -46,6 +471,50 @@ fn default_chain() -> String {
     "chain".into()
 }
 
+fn default_chain_timeout() -> u64 {
+    3000
+}
+
+fn default_background_interval() -> u64 {
+    30
+}
+
+fn default_background_crossfade() -> u32 {
+    1000
+}
+
+fn default_background_fit() -> BackgroundFit {
+    BackgroundFit::Contain
+}
+
+fn default_background_color() -> String {
+    "#808080".into()
+}
+
+fn default_background_cache() -> bool {
+    true
+}
+
+fn default_background_cache_dir() -> String {
+    "/var/lib/lightdm/contest-greeter/wallpapers".into()
+}
+
+fn default_background_cache_size() -> u64 {
+    200
+}
+
+fn default_background_max_size() -> u64 {
+    25
+}
+
+fn default_background_max_dimension() -> i32 {
+    8192
+}
+
+fn default_background_timeout() -> u64 {
+    30
+}
+
 fn default_count_end_login() -> bool {
     true
 }
@@ -54,10 +523,46 @@ fn default_count_from() -> Option<u64> {
     Some(10)
 }
 
+fn default_theme_reload() -> bool {
+    true
+}
+
 fn default_countdown_label_color() -> String {
     "white".into()
 }
 
+fn default_visible() -> bool {
+    true
+}
+
+fn default_all_monitors() -> bool {
+    true
+}
+
+pub(crate) fn default_clock_format() -> String {
+    "%H:%M:%S".into()
+}
+
+fn default_show_schedule() -> bool {
+    true
+}
+
+fn default_clock_position() -> Anchor {
+    Anchor::TopLeft
+}
+
+fn default_high_contrast_keys() -> String {
+    "Ctrl+Alt+H".into()
+}
+
+fn default_large_text_keys() -> String {
+    "Ctrl+Alt+Plus".into()
+}
+
+fn default_announce_at() -> Vec<u64> {
+    vec![600, 300, 60, 30, 10, 0]
+}
+
 fn deserialize_end_time<'de, D>(deserializer: D) -> Result<Option<DateTime<FixedOffset>>, D::Error>
 where
     D: serde::Deserializer<'de>,
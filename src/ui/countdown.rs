@@ -5,20 +5,22 @@ use chrono::{DateTime, Local};
 use gtk4::Align;
 use gtk4::glib::{ControlFlow, SourceId, timeout_add_local, timeout_add_seconds_local};
 use gtk4::{
-    Box, CssProvider, Label, Overlay, STYLE_PROVIDER_PRIORITY_APPLICATION, gdk::Display,
-    prelude::*, style_context_add_provider_for_display,
+    AccessibleRole, Box, CssProvider, Label, STYLE_PROVIDER_PRIORITY_APPLICATION,
+    accessible::Property, gdk::Display, prelude::*, style_context_add_provider_for_display,
 };
 use log::debug;
 
+use crate::i18n;
 use crate::ui::UiConfig;
-use types::{CoreName, GreeterMessage, SystemSender};
+use crate::ui::accessibility::Announcer;
+use types::{ContestPhase, CoreName, GreeterMessage, LoginSource, SystemSender};
 
 pub struct CountDown<S: SystemSender + Clone + 'static> {
-    overlay: Overlay,
     label: Label,
     connection_dot: Box,
     state: Rc<RefCell<CountdownState>>,
     tick: Rc<RefCell<Option<SourceId>>>,
+    announcer: Announcer,
     bus: S,
 }
 
@@ -27,14 +29,15 @@ struct CountdownState {
     from_seconds: Option<u64>,
     end_login: bool,
     triggered: bool,
+    contest_ended: bool,
+    /// Seconds before the end at which the remaining time is announced to screen readers.
+    announce_at: Vec<u64>,
+    /// Remaining seconds at the last check, every milestone passed since then is announced.
+    last_remaining: Option<i64>,
 }
 
 impl<S: SystemSender + Clone + 'static> CountDown<S> {
-    pub fn new(conf: UiConfig, bus: S) -> Self {
-        let overlay = Overlay::new();
-        overlay.set_hexpand(true);
-        overlay.set_vexpand(true);
-
+    pub fn new(conf: UiConfig, bus: S, announcer: Announcer) -> Self {
         let connection_dot = Box::builder()
             .css_classes(vec!["green-dot"])
             .visible(false)
@@ -44,14 +47,16 @@ impl<S: SystemSender + Clone + 'static> CountDown<S> {
             .margin_end(20)
             .build();
 
-        let label = Label::new(Some(""));
+        let label = Label::builder()
+            .label("")
+            .accessible_role(AccessibleRole::Timer)
+            .build();
+        label.update_property(&[Property::Label(&i18n::tr("countdown-name"))]);
         label.style_context().add_class("countdown");
         label.set_halign(gtk4::Align::Center);
         label.set_valign(gtk4::Align::Center);
         label.set_hexpand(true);
         label.set_vexpand(true);
-        overlay.set_child(Some(&label));
-        overlay.add_overlay(&connection_dot);
 
         let css = CssProvider::new();
         debug!("{}", conf.countdown_label_color);
@@ -87,14 +92,17 @@ impl<S: SystemSender + Clone + 'static> CountDown<S> {
             from_seconds: conf.countdown_from,
             end_login: conf.countdown_end_login,
             triggered: false,
+            contest_ended: false,
+            announce_at: conf.accessibility.announce_at.clone(),
+            last_remaining: None,
         }));
 
         let countdown = Self {
-            overlay,
             label,
             connection_dot,
             state,
             tick: Rc::new(RefCell::new(None)),
+            announcer,
             bus,
         };
 
@@ -102,20 +110,28 @@ impl<S: SystemSender + Clone + 'static> CountDown<S> {
         countdown
     }
 
-    pub fn widget(&self) -> &Overlay {
-        &self.overlay
+    pub fn widget(&self) -> &Label {
+        &self.label
+    }
+
+    /// Dot shown while the countdown end time is known.
+    pub fn connection_dot(&self) -> &Box {
+        &self.connection_dot
     }
 
     pub fn update_endtime(&self, end_time: Option<DateTime<Local>>) {
         let mut state = self.state.borrow_mut();
-        if let Some(dt) = end_time {
-            state.end_time = Some(dt);
-            state.triggered = false;
-        }
+        state.end_time = end_time;
+        state.triggered = false;
         drop(state);
         self.schedule_timers();
     }
 
+    pub fn set_phase(&self, phase: ContestPhase) {
+        self.state.borrow_mut().contest_ended = phase == ContestPhase::Ended;
+        self.schedule_timers();
+    }
+
     fn schedule_timers(&self) {
         // cancel any existing tick
         if let Some(id) = self.tick.borrow_mut().take() {
@@ -125,19 +141,33 @@ impl<S: SystemSender + Clone + 'static> CountDown<S> {
         // update immediately so the label is in a correct state until the countdown starts
         update_ui(&self.label, &self.connection_dot, &self.state, &self.bus);
 
-        let state_snapshot = self.state.borrow();
+        let mut state_snapshot = self.state.borrow_mut();
+        // milestones already behind the new end time are not announced
+        state_snapshot.last_remaining = None;
         let Some(end_time) = state_snapshot.end_time else {
             return;
         };
-        let threshold_ms = state_snapshot.from_seconds.unwrap_or(0) as i64 * 1000;
-        let remaining_ms = (end_time - Local::now()).num_milliseconds();
-        let start_after_ms = (remaining_ms - threshold_ms).max(0) as u64;
+        if state_snapshot.contest_ended {
+            return;
+        }
+        let remaining = end_time - Local::now();
+        state_snapshot.last_remaining = Some(remaining.num_seconds());
+        // the ticker also has to run for the earliest announcement
+        let threshold = state_snapshot
+            .announce_at
+            .iter()
+            .copied()
+            .max()
+            .unwrap_or(0);
+        let threshold_ms = threshold.max(state_snapshot.from_seconds.unwrap_or(0)) as i64 * 1000;
+        let start_after_ms = (remaining.num_milliseconds() - threshold_ms).max(0) as u64;
         drop(state_snapshot);
 
         let label = self.label.clone();
         let connection_dot = self.connection_dot.clone();
         let state = self.state.clone();
         let tick_handle = self.tick.clone();
+        let announcer = self.announcer.clone();
         let bus = self.bus.clone();
 
         // wait until threshold is reached, then start a steady 1s ticker aligned to that moment
@@ -145,14 +175,17 @@ impl<S: SystemSender + Clone + 'static> CountDown<S> {
             std::time::Duration::from_millis(start_after_ms),
             move || {
                 update_ui(&label, &connection_dot, &state, &bus);
+                announce_milestones(&state, &announcer);
 
                 let label_tick = label.clone();
                 let connection_dot_tick = connection_dot.clone();
                 let state_tick = state.clone();
                 let tick_ref = tick_handle.clone();
+                let announcer_tick = announcer.clone();
                 let bus_tick = bus.clone();
                 let tick_id = timeout_add_seconds_local(1, move || {
                     update_ui(&label_tick, &connection_dot_tick, &state_tick, &bus_tick);
+                    announce_milestones(&state_tick, &announcer_tick);
                     ControlFlow::Continue
                 });
                 *tick_ref.borrow_mut() = Some(tick_id);
@@ -165,6 +198,40 @@ impl<S: SystemSender + Clone + 'static> CountDown<S> {
     }
 }
 
+/// Announces every milestone passed since the last check. The remaining time is recomputed on
+/// each tick, so a suspended or late timer cannot announce a stale value.
+fn announce_milestones(state: &Rc<RefCell<CountdownState>>, announcer: &Announcer) {
+    let mut state = state.borrow_mut();
+    let (Some(end_time), Some(last)) = (state.end_time, state.last_remaining) else {
+        return;
+    };
+    let remaining = (end_time - Local::now()).num_seconds();
+    state.last_remaining = Some(remaining);
+    // only the most recent milestone is worth reading when several were passed at once
+    let milestone = state
+        .announce_at
+        .iter()
+        .copied()
+        .filter(|&seconds| last > seconds as i64 && remaining <= seconds as i64)
+        .min();
+    drop(state);
+    if let Some(seconds) = milestone {
+        announcer.announce(&announcement(seconds));
+    }
+}
+
+/// Text read by screen readers when `seconds` remain.
+fn announcement(seconds: u64) -> String {
+    match seconds {
+        0 => i18n::tr("countdown-announce-start"),
+        s if s % 60 == 0 => i18n::tr_args(
+            "countdown-announce-minutes",
+            &[("minutes", (s / 60).into())],
+        ),
+        s => i18n::tr_args("countdown-announce-seconds", &[("seconds", s.into())]),
+    }
+}
+
 fn update_ui<S: SystemSender>(
     label: &Label,
     connection_dot: &Box,
@@ -173,6 +240,11 @@ fn update_ui<S: SystemSender>(
 ) {
     let now = Local::now();
     let mut state = state.borrow_mut();
+    if state.contest_ended {
+        // the contest is over, there is nothing left to count down to
+        label.set_text("");
+        return;
+    }
     if let Some(end_time) = state.end_time {
         let remaining = end_time - now;
         let seconds = remaining.num_seconds();
@@ -186,9 +258,12 @@ fn update_ui<S: SystemSender>(
             if seconds > 0 {
                 label.set_text(&format!("{}", seconds));
             } else {
-                label.set_text("Starting...");
+                label.set_text(&i18n::tr("countdown-starting"));
                 if state.end_login && !state.triggered {
-                    bus.send_to(CoreName::Greeter, GreeterMessage::Login());
+                    bus.send_to(
+                        CoreName::Greeter,
+                        GreeterMessage::Login(LoginSource::Countdown),
+                    );
                     state.triggered = true;
                 }
             }
@@ -0,0 +1,225 @@
+use chrono::Local;
+use gtk4::{
+    Align, Box as GTBox, CssProvider, Label, Orientation, PolicyType,
+    STYLE_PROVIDER_PRIORITY_APPLICATION, ScrolledWindow, gdk::Display, pango::EllipsizeMode,
+    prelude::*, style_context_add_provider_for_display,
+};
+use types::GreeterStatus;
+
+use crate::{
+    conf::{Conf, masked_toml},
+    host, logging,
+    metrics::CLOCK_OFFSET,
+};
+
+/// Number of recent log lines shown on the panel.
+const LOG_LINES: usize = 15;
+
+/// Details that don't change while the greeter runs.
+pub struct DiagnosticsInfo {
+    config: String,
+    api_url: Option<String>,
+}
+
+impl DiagnosticsInfo {
+    pub fn new(conf: &Conf) -> Self {
+        Self {
+            config: masked_toml(conf),
+            api_url: conf.api_poller.url().map(str::to_string),
+        }
+    }
+}
+
+/// Hidden panel with machine details for floor staff, opened with a staff chain or D-Bus.
+#[derive(Clone)]
+pub struct DiagnosticsPanel {
+    container: GTBox,
+    details: Label,
+    log: Label,
+    api_url: Option<String>,
+}
+
+const DIAGNOSTICS_CSS: &str = "
+    .diagnostics {
+        background-color: rgba(20, 20, 20, 0.92);
+        border-radius: 12px;
+        padding: 24px 32px;
+    }
+
+    .diagnostics label {
+        color: white;
+        font-size: 16px;
+    }
+
+    .diagnostics label.diagnostics-title {
+        font-size: 28px;
+        font-weight: bold;
+    }
+
+    .diagnostics label.diagnostics-heading {
+        font-size: 18px;
+        font-weight: bold;
+    }
+
+    .diagnostics label.diagnostics-details,
+    .diagnostics label.diagnostics-config,
+    .diagnostics label.diagnostics-log {
+        font-family: monospace;
+    }
+
+    .diagnostics label.diagnostics-config,
+    .diagnostics label.diagnostics-log {
+        font-size: 13px;
+    }
+";
+
+impl DiagnosticsPanel {
+    pub fn new(info: DiagnosticsInfo) -> Self {
+        let container = GTBox::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(12)
+            .halign(Align::Center)
+            .valign(Align::Center)
+            .visible(false)
+            .build();
+        container.add_css_class("diagnostics");
+
+        let title = Label::new(Some("Diagnostics"));
+        title.add_css_class("diagnostics-title");
+        let details = Label::builder()
+            .halign(Align::Start)
+            .selectable(true)
+            .build();
+        details.add_css_class("diagnostics-details");
+
+        let config = Label::builder()
+            .label(&info.config)
+            .halign(Align::Start)
+            .valign(Align::Start)
+            .selectable(true)
+            .build();
+        config.add_css_class("diagnostics-config");
+        let config_scroll = ScrolledWindow::builder()
+            .hscrollbar_policy(PolicyType::Never)
+            .min_content_height(200)
+            .min_content_width(900)
+            .child(&config)
+            .build();
+
+        let log = Label::builder()
+            .halign(Align::Start)
+            .max_width_chars(120)
+            .ellipsize(EllipsizeMode::End)
+            .selectable(true)
+            .build();
+        log.add_css_class("diagnostics-log");
+
+        container.append(&title);
+        container.append(&details);
+        container.append(&heading("Configuration"));
+        container.append(&config_scroll);
+        container.append(&heading("Recent log"));
+        container.append(&log);
+
+        let css = CssProvider::new();
+        css.load_from_data(DIAGNOSTICS_CSS);
+        if let Some(display) = Display::default() {
+            style_context_add_provider_for_display(
+                &display,
+                &css,
+                STYLE_PROVIDER_PRIORITY_APPLICATION,
+            );
+        }
+
+        Self {
+            container,
+            details,
+            log,
+            api_url: info.api_url,
+        }
+    }
+
+    pub fn widget(&self) -> &GTBox {
+        &self.container
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.container.is_visible()
+    }
+
+    pub fn set_visible(&self, visible: bool) {
+        self.container.set_visible(visible);
+    }
+
+    /// Fills the panel, `status` is `None` when the status core did not answer.
+    pub fn refresh(&self, status: Option<&GreeterStatus>) {
+        let unknown = || "unknown".to_string();
+        let interfaces = host::interface_addresses()
+            .into_iter()
+            .map(|(name, address)| format!("{name} {address}"))
+            .collect::<Vec<_>>();
+        let clock_offset = CLOCK_OFFSET
+            .get(&[])
+            .map(|offset| format!("{offset:+.3}s"))
+            .unwrap_or_else(unknown);
+        let last_poll = status
+            .map(|status| {
+                let at = status
+                    .contest_api_polled
+                    .map(|at| at.format(" at %H:%M:%S").to_string())
+                    .unwrap_or_default();
+                format!("{}{at}", status.contest_api)
+            })
+            .unwrap_or_else(unknown);
+        let last_error = status
+            .and_then(|status| status.last_error.as_ref())
+            .map(|e| format!("{} {}", e.time.format("%H:%M:%S"), e.message))
+            .unwrap_or_else(|| "none".into());
+
+        let lines = [
+            ("Version", env!("CARGO_PKG_VERSION").to_string()),
+            ("Host", host::hostname().unwrap_or_else(unknown)),
+            ("Network", interfaces.join(", ")),
+            ("Time", Local::now().format("%Y-%m-%d %H:%M:%S").to_string()),
+            ("Clock", format!("offset to contest API {clock_offset}")),
+            (
+                "API URL",
+                self.api_url.clone().unwrap_or_else(|| "not set".into()),
+            ),
+            ("Last poll", last_poll),
+            (
+                "D-Bus",
+                status
+                    .map(|status| status.dbus.to_string())
+                    .unwrap_or_else(unknown),
+            ),
+            (
+                "Login",
+                status
+                    .map(|status| status.auth.to_string())
+                    .unwrap_or_else(unknown),
+            ),
+            (
+                "Wallpaper",
+                status
+                    .map(|status| status.wallpaper.to_string())
+                    .unwrap_or_else(unknown),
+            ),
+            ("Last error", last_error),
+        ];
+        let text = lines
+            .iter()
+            .map(|(key, value)| format!("{key:<10} {value}"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        self.details.set_text(&text);
+        self.log
+            .set_text(&logging::recent_lines(LOG_LINES).join("\n"));
+    }
+}
+
+fn heading(text: &str) -> Label {
+    let label = Label::builder().label(text).halign(Align::Start).build();
+    label.add_css_class("diagnostics-heading");
+    label
+}
@@ -0,0 +1,142 @@
+use gtk4::{
+    AccessibleRole, Align, Box as GTBox, CssProvider, Label, Orientation,
+    STYLE_PROVIDER_PRIORITY_APPLICATION,
+    accessible::Property,
+    gdk::Display,
+    glib::{ControlFlow, timeout_add_seconds_local},
+    prelude::*,
+    style_context_add_provider_for_display,
+};
+
+use crate::host;
+use crate::i18n;
+use crate::ui::config::IdentityConfig;
+
+/// Seconds between reads of the IP addresses, which may change after the greeter started.
+const IP_REFRESH_INTERVAL: u32 = 10;
+
+/// Panel showing which team and seat this machine belongs to, for floor volunteers.
+pub struct IdentityPanel {
+    container: GTBox,
+    team_name: Label,
+}
+
+const IDENTITY_CSS: &str = "
+    .identity-panel {
+        background-color: rgba(30, 30, 30, 0.8);
+        border-radius: 12px;
+        padding: 16px 24px;
+        margin: 20px;
+    }
+
+    .identity-panel label {
+        color: white;
+        font-size: 18px;
+    }
+
+    .identity-panel label.identity-team {
+        font-size: 32px;
+        font-weight: bold;
+    }
+";
+
+impl IdentityPanel {
+    pub fn new(conf: &IdentityConfig) -> Self {
+        let container = GTBox::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(4)
+            .halign(Align::Start)
+            .valign(Align::End)
+            .visible(conf.enabled)
+            .accessible_role(AccessibleRole::Group)
+            .build();
+        container.update_property(&[Property::Label(&i18n::tr("identity-name"))]);
+        container.add_css_class("identity-panel");
+
+        let hostname = host::hostname();
+        let host_entry = hostname
+            .as_ref()
+            .and_then(|name| conf.hosts.iter().find(|h| &h.hostname == name));
+        let team_name = host_entry
+            .and_then(|h| h.team_name.clone())
+            .or_else(|| conf.team_name.clone());
+        let seat = host_entry
+            .and_then(|h| h.seat.clone())
+            .or_else(|| conf.seat.clone());
+
+        let team_label = info_label(team_name.as_deref(), "identity-team");
+        container.append(&team_label);
+        container.append(&info_label(
+            seat.map(|s| i18n::tr_args("identity-seat", &[("seat", s.into())]))
+                .as_deref(),
+            "identity-seat",
+        ));
+        container.append(&info_label(
+            hostname
+                .map(|h| i18n::tr_args("identity-host", &[("host", h.into())]))
+                .as_deref(),
+            "identity-host",
+        ));
+
+        let ip_label = info_label(None, "identity-ip");
+        update_addresses(&ip_label);
+        container.append(&ip_label);
+        if conf.enabled {
+            timeout_add_seconds_local(IP_REFRESH_INTERVAL, move || {
+                update_addresses(&ip_label);
+                ControlFlow::Continue
+            });
+        }
+
+        let css = CssProvider::new();
+        css.load_from_data(IDENTITY_CSS);
+        if let Some(display) = Display::default() {
+            style_context_add_provider_for_display(
+                &display,
+                &css,
+                STYLE_PROVIDER_PRIORITY_APPLICATION,
+            );
+        }
+
+        Self {
+            container,
+            team_name: team_label,
+        }
+    }
+
+    pub fn widget(&self) -> &GTBox {
+        &self.container
+    }
+
+    pub fn set_team_name(&self, name: &str) {
+        self.team_name.set_text(name);
+        self.team_name.set_visible(true);
+    }
+}
+
+fn update_addresses(label: &Label) {
+    let addresses = host::ip_addresses()
+        .into_iter()
+        .filter(|a| a.is_ipv4())
+        .map(|a| a.to_string())
+        .collect::<Vec<_>>();
+    if addresses.is_empty() {
+        label.set_visible(false);
+        return;
+    }
+    let text = i18n::tr_args("identity-ip", &[("addresses", addresses.join(", ").into())]);
+    if label.text() != text {
+        label.set_text(&text);
+    }
+    label.set_visible(true);
+}
+
+fn info_label(text: Option<&str>, class: &str) -> Label {
+    let label = Label::builder()
+        .label(text.unwrap_or_default())
+        .visible(text.is_some())
+        .halign(Align::Start)
+        .build();
+    label.add_css_class(class);
+    label
+}
@@ -0,0 +1,190 @@
+use std::{cell::Cell, fs, io::Read, path::Path, rc::Rc};
+
+use anyhow::{Context, Result, anyhow};
+use gtk4::{
+    gdk_pixbuf::{Colorspace, Pixbuf, PixbufLoader, prelude::PixbufLoaderExt},
+    glib,
+};
+
+/// Image formats accepted as wallpaper, detected from the file contents.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ImageFormat {
+    Jpeg,
+    Png,
+    Webp,
+    Svg,
+    Avif,
+}
+
+impl ImageFormat {
+    /// Name of the gdk-pixbuf loader that decodes this format.
+    fn loader_name(self) -> &'static str {
+        match self {
+            ImageFormat::Jpeg => "jpeg",
+            ImageFormat::Png => "png",
+            ImageFormat::Webp => "webp",
+            ImageFormat::Svg => "svg",
+            ImageFormat::Avif => "avif",
+        }
+    }
+}
+
+/// Limits protecting the greeter against huge or malicious wallpapers.
+#[derive(Clone, Copy, Debug)]
+pub struct ImageLimits {
+    pub max_bytes: u64,
+    pub max_dimension: i32,
+}
+
+/// Pixels of a decoded wallpaper. Unlike a `Pixbuf` they can be handed from the download thread
+/// to the UI, so an image is decoded once, off the main thread.
+pub struct DecodedImage {
+    pixels: glib::Bytes,
+    has_alpha: bool,
+    bits_per_sample: i32,
+    width: i32,
+    height: i32,
+    rowstride: i32,
+}
+
+impl DecodedImage {
+    fn new(pixbuf: &Pixbuf) -> Self {
+        Self {
+            pixels: pixbuf.read_pixel_bytes(),
+            has_alpha: pixbuf.has_alpha(),
+            bits_per_sample: pixbuf.bits_per_sample(),
+            width: pixbuf.width(),
+            height: pixbuf.height(),
+            rowstride: pixbuf.rowstride(),
+        }
+    }
+
+    /// Wraps the pixels without copying or decoding them again.
+    pub fn pixbuf(&self) -> Pixbuf {
+        Pixbuf::from_bytes(
+            &self.pixels,
+            Colorspace::Rgb,
+            self.has_alpha,
+            self.bits_per_sample,
+            self.width,
+            self.height,
+            self.rowstride,
+        )
+    }
+}
+
+/// Detects the image format from its magic bytes.
+pub fn detect_format(header: &[u8]) -> Option<ImageFormat> {
+    if header.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        return Some(ImageFormat::Jpeg);
+    }
+    if header.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return Some(ImageFormat::Png);
+    }
+    if header.len() >= 12 && &header[0..4] == b"RIFF" && &header[8..12] == b"WEBP" {
+        return Some(ImageFormat::Webp);
+    }
+    if header.len() >= 12 && &header[4..8] == b"ftyp" && matches!(&header[8..12], b"avif" | b"avis")
+    {
+        return Some(ImageFormat::Avif);
+    }
+
+    let text = String::from_utf8_lossy(header);
+    let text = text.trim_start_matches('\u{feff}').trim_start();
+    if (text.starts_with("<?xml") || text.starts_with("<svg") || text.starts_with("<!--"))
+        && text.contains("<svg")
+    {
+        return Some(ImageFormat::Svg);
+    }
+    None
+}
+
+/// Fails when gdk-pixbuf has no loader installed for `format`.
+pub fn ensure_supported(format: ImageFormat) -> Result<()> {
+    let loader = format.loader_name();
+    let supported = Pixbuf::formats()
+        .iter()
+        .filter_map(|f| f.name())
+        .any(|name| name.as_str() == loader || (loader == "avif" && name.as_str() == "heif"));
+    if supported {
+        Ok(())
+    } else {
+        Err(anyhow!(
+            "no gdk-pixbuf loader installed for {format:?} images"
+        ))
+    }
+}
+
+/// Checks a local wallpaper without decoding it.
+pub fn check_file(path: &Path, limits: ImageLimits) -> Result<ImageFormat> {
+    let size = fs::metadata(path)
+        .context(format!("invalid filepath {}", path.display()))?
+        .len();
+    if size > limits.max_bytes {
+        return Err(anyhow!(
+            "file is too large ({} MB, max {} MB)",
+            size / 1024 / 1024,
+            limits.max_bytes / 1024 / 1024
+        ));
+    }
+
+    let mut header = Vec::with_capacity(512);
+    fs::File::open(path)
+        .context(format!("opening {}", path.display()))?
+        .take(512)
+        .read_to_end(&mut header)?;
+    let format = detect_format(&header).ok_or(anyhow!("unrecognized image format"))?;
+    ensure_supported(format)?;
+
+    // vector images have no fixed size, they are rendered at the size of the screen
+    if format != ImageFormat::Svg {
+        let (_, width, height) =
+            Pixbuf::file_info(path).ok_or(anyhow!("failed to read image dimensions"))?;
+        check_dimensions(width, height, limits)?;
+    }
+    Ok(format)
+}
+
+/// Decodes a downloaded wallpaper, refusing it before allocating when it is too large.
+pub fn decode_bytes(bytes: &[u8], limits: ImageLimits) -> Result<DecodedImage> {
+    let format = detect_format(bytes).ok_or(anyhow!("unrecognized image format"))?;
+    ensure_supported(format)?;
+
+    let loader = PixbufLoader::new();
+    let too_large = Rc::new(Cell::new(None));
+    let too_large_handle = too_large.clone();
+    loader.connect_size_prepared(move |loader, width, height| {
+        if check_dimensions(width, height, limits).is_err() {
+            too_large_handle.set(Some((width, height)));
+            // shrink the output to avoid allocating the full image before we bail out
+            loader.set_size(1, 1);
+        }
+    });
+
+    let written = loader.write(bytes).and_then(|_| loader.close());
+    if let Some((width, height)) = too_large.get() {
+        return Err(dimension_error(width, height, limits));
+    }
+    written
+        .map_err(|e| anyhow!(e))
+        .context("Failed to decode image data (invalid format?)")?;
+    loader
+        .pixbuf()
+        .map(|pixbuf| DecodedImage::new(&pixbuf))
+        .ok_or(anyhow!("Failed to decode image data (no image produced)"))
+}
+
+fn check_dimensions(width: i32, height: i32, limits: ImageLimits) -> Result<()> {
+    if width > limits.max_dimension || height > limits.max_dimension {
+        Err(dimension_error(width, height, limits))
+    } else {
+        Ok(())
+    }
+}
+
+fn dimension_error(width: i32, height: i32, limits: ImageLimits) -> anyhow::Error {
+    anyhow!(
+        "image is too large ({width}x{height}, max {0}x{0})",
+        limits.max_dimension
+    )
+}
@@ -0,0 +1,84 @@
+use std::path::Path;
+
+use gtk4::{
+    ContentFit, CssProvider, Label, Overlay, Picture, STYLE_PROVIDER_PRIORITY_APPLICATION, Widget,
+    gdk::Display, pango::WrapMode, prelude::*, style_context_add_provider_for_display,
+};
+use log::warn;
+
+use crate::ui::config::{Anchor, LayoutConfig, Placement};
+
+const LAYOUT_CSS: &str = "
+    label.layout-text {
+        font-size: 24px;
+        color: white;
+    }
+";
+
+/// Adds `widget` to `overlay` at the position configured in `placement`, unless it is hidden.
+pub fn place(overlay: &Overlay, widget: &impl IsA<Widget>, placement: &Placement) {
+    if !placement.visible {
+        return;
+    }
+    if let Some(anchor) = placement.anchor {
+        let (halign, valign) = anchor.align();
+        widget.set_halign(halign);
+        widget.set_valign(valign);
+    }
+    if let Some(margin) = placement.margin {
+        widget.set_margin_top(margin);
+        widget.set_margin_bottom(margin);
+        widget.set_margin_start(margin);
+        widget.set_margin_end(margin);
+    }
+    if placement.width.is_some() || placement.height.is_some() {
+        widget.set_size_request(
+            placement.width.unwrap_or(-1),
+            placement.height.unwrap_or(-1),
+        );
+    }
+    overlay.add_overlay(widget);
+}
+
+/// Adds the images and text blocks from the layout config.
+pub fn add_extras(overlay: &Overlay, layout: &LayoutConfig) {
+    let css = CssProvider::new();
+    css.load_from_data(LAYOUT_CSS);
+    if let Some(display) = Display::default() {
+        style_context_add_provider_for_display(&display, &css, STYLE_PROVIDER_PRIORITY_APPLICATION);
+    }
+
+    for image in &layout.images {
+        if !Path::new(&image.source).is_file() {
+            warn!("[UI] layout image {} does not exist", image.source);
+            continue;
+        }
+        let picture = Picture::for_filename(&image.source);
+        picture.set_content_fit(ContentFit::Contain);
+        picture.set_can_shrink(true);
+        picture.add_css_class("layout-image");
+        anchor_default(&picture);
+        place(overlay, &picture, &image.placement);
+    }
+
+    for text in &layout.texts {
+        let label = Label::builder()
+            .label(&text.text)
+            .wrap(true)
+            .wrap_mode(WrapMode::WordChar)
+            .build();
+        label.add_css_class("layout-text");
+        if let Some(class) = &text.class {
+            label.add_css_class(class);
+        }
+        anchor_default(&label);
+        place(overlay, &label, &text.placement);
+    }
+}
+
+/// Extra widgets are centred unless an anchor is configured.
+fn anchor_default(widget: &impl IsA<Widget>) {
+    let (halign, valign) = Anchor::Center.align();
+    widget.set_halign(halign);
+    widget.set_valign(valign);
+}
@@ -1,14 +1,22 @@
-use std::rc::Rc;
+use std::{
+    cell::{Cell, RefCell},
+    rc::Rc,
+};
 
 use gtk4::{
-    Align, Box as GTBox, Button, CssProvider, Entry, EventControllerKey, InputPurpose, Label,
-    Orientation, Overlay, PropagationPhase, STYLE_PROVIDER_PRIORITY_APPLICATION,
-    gdk::{Display, Key},
+    AccessibleRole, Align, Box as GTBox, Button, CssProvider, DropDown, Entry, EntryIconPosition,
+    EventControllerKey, InputPurpose, Label, Orientation, Overlay, PropagationPhase,
+    STYLE_PROVIDER_PRIORITY_APPLICATION, StringList,
+    accessible::{Property, Relation},
+    gdk::{Device, Display, Key, ModifierType},
     glib::Propagation,
     pango::WrapMode,
     prelude::*,
     style_context_add_provider_for_display,
 };
+use types::KeyboardLayout;
+
+use crate::i18n;
 
 #[derive(Clone)]
 pub struct LoginUi {
@@ -16,6 +24,16 @@ pub struct LoginUi {
     username: Entry,
     password: Entry,
     label: Label,
+    lock_warning: Label,
+    caps_lock: Rc<Cell<bool>>,
+    /// Set when a keypad key arrived as a navigation key, so keypad digits are not typed.
+    num_lock_off: Rc<Cell<bool>>,
+    login_button: Button,
+    layout_row: GTBox,
+    layout_selector: DropDown,
+    layouts: Rc<RefCell<Vec<KeyboardLayout>>>,
+    /// Set while the selector is filled, so that doesn't count as a choice.
+    updating_layouts: Rc<Cell<bool>>,
     login_callback: Rc<Box<dyn Fn(String, String)>>,
 }
 
@@ -36,21 +54,44 @@ const LOGIN_CSS: &str = "
     .error-label {
         color: #EE4B2B;
     }
+
+    .login-lock-warning {
+        color: #f1c40f;
+    }
+
+    button.login-button {
+        margin-top: 6px;
+    }
+
+    .login-layout-label {
+        color: white;
+    }
 ";
 
 impl LoginUi {
-    pub fn new(login_callback: Box<dyn Fn(String, String)>) -> Self {
+    pub fn new(
+        login_callback: Box<dyn Fn(String, String)>,
+        layout_callback: Box<dyn Fn(String)>,
+    ) -> Self {
         let container = build_container();
         container.set_hexpand(true);
         container.set_vexpand(true);
         let content = build_content();
         load_css();
 
-        let username = build_entry("username", false);
-        let password = build_entry("password", true);
+        let username = build_entry(&i18n::tr("login-username"), false);
+        username.add_css_class("login-username");
+        let password = build_entry(&i18n::tr("login-password"), true);
+        password.add_css_class("login-password");
         let label = build_label();
+        let lock_warning = build_lock_warning();
+        let (layout_row, layout_selector) = build_layout_row();
+        let login_button = build_login_button();
         content.append(&username);
         content.append(&password);
+        content.append(&lock_warning);
+        content.append(&layout_row);
+        content.append(&login_button);
         content.append(&label);
         container.set_child(Some(&content));
         let login_ui = Self {
@@ -58,9 +99,33 @@ impl LoginUi {
             username,
             password,
             label,
+            lock_warning,
+            caps_lock: Rc::new(Cell::new(false)),
+            num_lock_off: Rc::new(Cell::new(false)),
+            login_button,
+            layout_row,
+            layout_selector,
+            layouts: Rc::new(RefCell::new(Vec::new())),
+            updating_layouts: Rc::new(Cell::new(false)),
             login_callback: Rc::new(login_callback),
         };
 
+        {
+            let layouts = login_ui.layouts.clone();
+            let updating = login_ui.updating_layouts.clone();
+            login_ui
+                .layout_selector
+                .connect_selected_notify(move |selector| {
+                    if updating.get() {
+                        return;
+                    }
+                    let layouts = layouts.borrow();
+                    if let Some(layout) = layouts.get(selector.selected() as usize) {
+                        layout_callback(layout.name.clone());
+                    }
+                });
+        }
+
         let close_button = build_close_button(&login_ui);
         login_ui.container.add_overlay(&close_button);
         login_ui.container.set_visible(false);
@@ -69,20 +134,13 @@ impl LoginUi {
     }
 
     pub fn init(&self) {
-        attach_submit_handler(
-            &self.username,
-            &self.username,
-            &self.password,
-            &self.label,
-            &self.login_callback,
-        );
-        attach_submit_handler(
-            &self.password,
-            &self.username,
-            &self.password,
-            &self.label,
-            &self.login_callback,
-        );
+        attach_submit_handler(&self.username, self);
+        attach_submit_handler(&self.password, self);
+        attach_lock_tracking(self);
+
+        let login_ui = self.clone();
+        self.login_button
+            .connect_clicked(move |_| login_ui.submit());
     }
 
     pub fn widget(&self) -> &Overlay {
@@ -96,13 +154,101 @@ impl LoginUi {
         // grab focus if the previous state was hidden
         if !visible {
             self.username.grab_focus();
+            if let Some(keyboard) = keyboard() {
+                self.caps_lock.set(keyboard.is_caps_locked());
+                self.num_lock_off
+                    .set(self.num_lock_off.get() && !keyboard.is_num_locked());
+            }
+            self.update_lock_warning();
+        } else {
+            set_revealed(&self.password, false);
+        }
+    }
+
+    pub fn is_visible(&self) -> bool {
+        self.container.is_visible()
+    }
+
+    /// Fills the layout selector and shows the active layout in the password field.
+    pub fn set_layouts(&self, layouts: Vec<KeyboardLayout>, active: Option<&str>) {
+        let descriptions = layouts
+            .iter()
+            .map(|layout| layout.description.as_str())
+            .collect::<Vec<_>>();
+        let active_index = layouts
+            .iter()
+            .position(|layout| Some(layout.name.as_str()) == active);
+
+        self.updating_layouts.set(true);
+        self.layout_selector
+            .set_model(Some(&StringList::new(&descriptions)));
+        if let Some(index) = active_index {
+            self.layout_selector.set_selected(index as u32);
         }
+        self.updating_layouts.set(false);
+
+        let placeholder = match active_index.map(|index| &layouts[index]) {
+            Some(layout) => i18n::tr_args(
+                "login-password-layout",
+                &[("layout", layout.short.as_str().into())],
+            ),
+            None => i18n::tr("login-password"),
+        };
+        self.password.set_placeholder_text(Some(&placeholder));
+        if let Some(index) = active_index {
+            self.password
+                .update_property(&[Property::Description(&layouts[index].description)]);
+        }
+        self.layout_row.set_visible(!layouts.is_empty());
+        *self.layouts.borrow_mut() = layouts;
     }
 
     pub fn set_error_text(&self, label: &str) {
         self.label.set_label(label);
         self.label.set_visible(true);
     }
+
+    /// Sends the credentials to the greeter when both are filled in.
+    fn submit(&self) {
+        let username = self.username.text().to_string();
+        let password = self.password.text().to_string();
+        if !username.is_empty() && !password.is_empty() {
+            self.label.set_visible(false);
+            (self.login_callback)(username, password);
+        }
+    }
+
+    /// Warns about lock keys that change what the password fields receive.
+    fn update_lock_warning(&self) {
+        let mut warnings = Vec::new();
+        if self.caps_lock.get() {
+            warnings.push(i18n::tr("login-caps-lock"));
+        }
+        if self.num_lock_off.get() {
+            warnings.push(i18n::tr("login-num-lock"));
+        }
+        self.lock_warning.set_text(&warnings.join(" · "));
+        self.lock_warning.set_visible(!warnings.is_empty());
+    }
+}
+
+/// Keys the keypad digits produce while Num Lock is off.
+const KEYPAD_WITHOUT_NUM_LOCK: &[Key] = &[
+    Key::KP_Insert,
+    Key::KP_End,
+    Key::KP_Down,
+    Key::KP_Page_Down,
+    Key::KP_Left,
+    Key::KP_Begin,
+    Key::KP_Right,
+    Key::KP_Home,
+    Key::KP_Up,
+    Key::KP_Page_Up,
+    Key::KP_Delete,
+];
+
+fn keyboard() -> Option<Device> {
+    Display::default()?.default_seat()?.keyboard()
 }
 
 fn build_container() -> Overlay {
@@ -122,7 +268,12 @@ fn load_css() {
 }
 
 fn build_content() -> GTBox {
-    let content = GTBox::new(Orientation::Vertical, 6);
+    let content = GTBox::builder()
+        .orientation(Orientation::Vertical)
+        .spacing(6)
+        .accessible_role(AccessibleRole::Form)
+        .build();
+    content.update_property(&[Property::Label(&i18n::tr("login-name"))]);
     content.set_halign(Align::Center);
     content.set_valign(Align::Center);
     content.set_width_request(300);
@@ -133,13 +284,67 @@ fn build_content() -> GTBox {
 fn build_entry(placeholder: &str, secret: bool) -> Entry {
     let entry = Entry::new();
     entry.set_placeholder_text(Some(placeholder));
+    // the placeholder disappears while typing, screen readers need a name that stays
+    entry.update_property(&[Property::Label(placeholder)]);
     if secret {
-        entry.set_visibility(false);
         entry.set_input_purpose(InputPurpose::Password);
+        set_revealed(&entry, false);
+        entry.set_icon_activatable(EntryIconPosition::Secondary, true);
+        entry.connect_icon_press(|entry, position| {
+            if position == EntryIconPosition::Secondary {
+                set_revealed(entry, !EntryExt::is_visible(entry));
+            }
+        });
     }
     entry
 }
 
+/// Shows or hides the text of a secret entry, with the icon offering the opposite.
+fn set_revealed(entry: &Entry, revealed: bool) {
+    entry.set_visibility(revealed);
+    let (icon, tooltip) = if revealed {
+        ("view-conceal-symbolic", "login-hide-password")
+    } else {
+        ("view-reveal-symbolic", "login-show-password")
+    };
+    entry.set_icon_from_icon_name(EntryIconPosition::Secondary, Some(icon));
+    entry.set_icon_tooltip_text(EntryIconPosition::Secondary, Some(&i18n::tr(tooltip)));
+}
+
+fn build_lock_warning() -> Label {
+    // an alert, so screen readers read the warning when it appears
+    let label = Label::builder()
+        .accessible_role(AccessibleRole::Alert)
+        .visible(false)
+        .wrap(true)
+        .max_width_chars(30)
+        .build();
+    label.add_css_class("login-lock-warning");
+    label
+}
+
+fn build_login_button() -> Button {
+    let button = Button::with_label(&i18n::tr("login-button"));
+    button.add_css_class("suggested-action");
+    button.add_css_class("login-button");
+    button
+}
+
+fn build_layout_row() -> (GTBox, DropDown) {
+    let row = GTBox::new(Orientation::Horizontal, 6);
+    row.set_visible(false);
+    row.add_css_class("login-layout-row");
+
+    let label = Label::new(Some(&i18n::tr("login-keyboard")));
+    label.add_css_class("login-layout-label");
+    let selector = DropDown::builder().hexpand(true).build();
+    selector.update_relation(&[Relation::LabelledBy(&[label.upcast_ref()])]);
+    selector.add_css_class("login-layout");
+    row.append(&label);
+    row.append(&selector);
+    (row, selector)
+}
+
 fn build_label() -> Label {
     let label = Label::builder()
         .visible(false)
@@ -151,29 +356,14 @@ fn build_label() -> Label {
     label
 }
 
-fn attach_submit_handler(
-    trigger: &Entry,
-    username_entry: &Entry,
-    password_entry: &Entry,
-    label: &Label,
-    callback: &Rc<Box<dyn Fn(String, String)>>,
-) {
+fn attach_submit_handler(trigger: &Entry, login_ui: &LoginUi) {
     let controller = EventControllerKey::new();
     controller.set_propagation_phase(PropagationPhase::Capture);
 
-    let username_clone = username_entry.clone();
-    let password_clone = password_entry.clone();
-    let label_clone = label.clone();
-    let callback = callback.clone();
-
+    let login_ui = login_ui.clone();
     controller.connect_key_pressed(move |_, key, _, _| {
-        if key == Key::Return {
-            let username_val = username_clone.text().to_string();
-            let password_val = password_clone.text().to_string();
-            if !username_val.is_empty() && !password_val.is_empty() {
-                label_clone.set_visible(false);
-                callback(username_val, password_val);
-            }
+        if key == Key::Return || key == Key::KP_Enter {
+            login_ui.submit();
         }
         Propagation::Proceed
     });
@@ -181,12 +371,50 @@ fn attach_submit_handler(
     trigger.add_controller(controller);
 }
 
+/// Follows Caps Lock through the modifier state of key events on the panel, and Num Lock
+/// through keypad keys that arrive without it.
+fn attach_lock_tracking(login_ui: &LoginUi) {
+    let controller = EventControllerKey::new();
+    controller.set_propagation_phase(PropagationPhase::Capture);
+
+    let ui = login_ui.clone();
+    controller.connect_modifiers(move |_, state| {
+        ui.caps_lock.set(state.contains(ModifierType::LOCK_MASK));
+        ui.update_lock_warning();
+        Propagation::Proceed
+    });
+
+    let ui = login_ui.clone();
+    controller.connect_key_pressed(move |_, key, _, _| {
+        if KEYPAD_WITHOUT_NUM_LOCK.contains(&key) {
+            ui.num_lock_off.set(true);
+            ui.update_lock_warning();
+        }
+        Propagation::Proceed
+    });
+
+    // the lock state of the device is only updated once the key is released
+    let ui = login_ui.clone();
+    controller.connect_key_released(move |_, key, _, _| {
+        if (key == Key::Caps_Lock || key == Key::Num_Lock)
+            && let Some(keyboard) = keyboard()
+        {
+            ui.caps_lock.set(keyboard.is_caps_locked());
+            ui.num_lock_off.set(!keyboard.is_num_locked());
+            ui.update_lock_warning();
+        }
+    });
+
+    login_ui.container.add_controller(controller);
+}
+
 fn build_close_button(login_ui: &LoginUi) -> Button {
     let button = Button::builder()
         .icon_name("window-close-symbolic")
         .focus_on_click(false)
         .has_frame(false)
         .build();
+    button.update_property(&[Property::Label(&i18n::tr("login-close"))]);
     button.add_css_class("titlebutton");
     button.add_css_class("close");
     button.set_halign(Align::End);
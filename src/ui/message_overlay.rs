@@ -0,0 +1,150 @@
+use std::{cell::RefCell, rc::Rc};
+
+use chrono::Local;
+use gtk4::{
+    AccessibleRole, Align, Box as GTBox, CssProvider, Label, Orientation,
+    STYLE_PROVIDER_PRIORITY_APPLICATION,
+    accessible::Property,
+    gdk::Display,
+    glib::{ControlFlow, SourceId, timeout_add_local},
+    pango::WrapMode,
+    prelude::*,
+    style_context_add_provider_for_display,
+};
+use types::{Announcement, Severity};
+
+use crate::i18n;
+
+/// Announcement panel shown above the countdown, e.g. "Start delayed by 10 minutes".
+pub struct MessageOverlay {
+    container: GTBox,
+    title: Label,
+    body: Label,
+    expiry: Rc<RefCell<Option<SourceId>>>,
+}
+
+const MESSAGE_CSS: &str = "
+    .message-overlay {
+        background-color: rgba(30, 30, 30, 0.9);
+        border-radius: 12px;
+        border-left: 8px solid #3498db;
+        padding: 24px 32px;
+        margin-bottom: 64px;
+    }
+
+    .message-overlay.warning {
+        border-left-color: #f39c12;
+    }
+
+    .message-overlay.critical {
+        border-left-color: #e74c3c;
+    }
+
+    .message-overlay label.message-title {
+        font-size: 36px;
+        font-weight: bold;
+        color: white;
+    }
+
+    .message-overlay label.message-body {
+        font-size: 24px;
+        color: white;
+    }
+";
+
+impl MessageOverlay {
+    pub fn new() -> Self {
+        let container = GTBox::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(12)
+            .halign(Align::Center)
+            .valign(Align::End)
+            .visible(false)
+            .accessible_role(AccessibleRole::Group)
+            .build();
+        container.update_property(&[Property::Label(&i18n::tr("message-name"))]);
+        container.add_css_class("message-overlay");
+
+        let title = Label::new(None);
+        title.add_css_class("message-title");
+
+        let body = Label::builder()
+            .wrap(true)
+            .wrap_mode(WrapMode::Word)
+            .max_width_chars(60)
+            .build();
+        body.add_css_class("message-body");
+
+        container.append(&title);
+        container.append(&body);
+
+        let css = CssProvider::new();
+        css.load_from_data(MESSAGE_CSS);
+        if let Some(display) = Display::default() {
+            style_context_add_provider_for_display(
+                &display,
+                &css,
+                STYLE_PROVIDER_PRIORITY_APPLICATION,
+            );
+        }
+
+        Self {
+            container,
+            title,
+            body,
+            expiry: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    pub fn widget(&self) -> &GTBox {
+        &self.container
+    }
+
+    pub fn show(&self, announcement: Announcement) {
+        self.cancel_expiry();
+
+        if let Some(expires_at) = announcement.expires_at {
+            let remaining_ms = (expires_at - Local::now()).num_milliseconds();
+            if remaining_ms <= 0 {
+                self.clear();
+                return;
+            }
+
+            let container = self.container.clone();
+            let expiry = self.expiry.clone();
+            let id = timeout_add_local(
+                std::time::Duration::from_millis(remaining_ms as u64),
+                move || {
+                    container.set_visible(false);
+                    expiry.borrow_mut().take();
+                    ControlFlow::Break
+                },
+            );
+            *self.expiry.borrow_mut() = Some(id);
+        }
+
+        self.container.remove_css_class("warning");
+        self.container.remove_css_class("critical");
+        match announcement.severity {
+            Severity::Info => {}
+            Severity::Warning => self.container.add_css_class("warning"),
+            Severity::Critical => self.container.add_css_class("critical"),
+        }
+
+        self.title.set_text(&announcement.title);
+        self.title.set_visible(!announcement.title.is_empty());
+        self.body.set_text(&announcement.body);
+        self.container.set_visible(true);
+    }
+
+    pub fn clear(&self) {
+        self.cancel_expiry();
+        self.container.set_visible(false);
+    }
+
+    fn cancel_expiry(&self) {
+        if let Some(id) = self.expiry.borrow_mut().take() {
+            id.remove();
+        }
+    }
+}
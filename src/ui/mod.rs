@@ -1,119 +1,378 @@
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+
+use chrono::Local;
 use gtk4::Window;
-use gtk4::gdk;
-use gtk4::glib::idle_add_local;
 use gtk4::glib::{ControlFlow, MainLoop};
+use gtk4::glib::{idle_add_local, spawn_future_local, timeout_add_seconds_local};
 use gtk4::prelude::*;
 
+mod accessibility;
 mod background;
 mod chain_listener;
+mod clock;
 mod config;
 mod countdown;
+mod diagnostics;
+mod identity;
+mod image_check;
+mod layout;
 mod login_ui;
-use chain_listener::register_chain_listener;
-use log::info;
+mod message_overlay;
+mod monitors;
+mod phase_banner;
+mod staff_attention;
+mod theme;
+mod wallpaper_cache;
+use chain_listener::{Chain, register_chain_listener};
+use log::{info, warn};
 use login_ui::LoginUi;
 use tokio::sync::mpsc;
+use types::Announcement;
 use types::CoreName;
 use types::GreeterMessage;
+use types::LoginSource;
+use types::Severity;
+use types::StatusMessage;
 use types::SystemBus;
 use types::SystemSender;
 use types::UiMessage;
 
+use crate::i18n;
+use crate::status::query_status;
+use crate::ui::accessibility::{Accessibility, Announcer};
 use crate::ui::background::Background;
+use crate::ui::clock::Clock;
+use crate::ui::config::ChainAction;
 use crate::ui::countdown::CountDown;
+use crate::ui::diagnostics::DiagnosticsPanel;
+use crate::ui::identity::IdentityPanel;
+use crate::ui::message_overlay::MessageOverlay;
+use crate::ui::monitors::{Monitors, Wallpaper};
+use crate::ui::phase_banner::PhaseBanner;
+use crate::ui::staff_attention::StaffAttention;
+use crate::ui::theme::Theme;
 pub use config::UiConfig;
+pub use diagnostics::DiagnosticsInfo;
+
+/// Time in which the reboot chain must be repeated to confirm the reboot.
+const REBOOT_CONFIRM_TIMEOUT: Duration = Duration::from_secs(5);
 
-pub async fn run_ui(bus: impl SystemBus, conf: UiConfig) {
+pub async fn run_ui(bus: impl SystemBus, conf: UiConfig, diagnostics: DiagnosticsInfo) {
     gtk4::init().expect("init gtk");
     let (tx, rx) = mpsc::channel::<UiMessage>(16);
     bus.register(CoreName::UI, tx);
+    bus.send_to(CoreName::Greeter, GreeterMessage::UiStarted);
 
-    build_ui(bus, rx, conf);
+    build_ui(bus, rx, conf, diagnostics);
 
     info!("[UI] running main loop");
     let main_loop = MainLoop::new(None, false);
     main_loop.run();
 }
 
-fn build_ui(bus: impl SystemSender, mut rx: mpsc::Receiver<UiMessage>, conf: UiConfig) {
+fn build_ui(
+    bus: impl SystemSender,
+    mut rx: mpsc::Receiver<UiMessage>,
+    conf: UiConfig,
+    diagnostics_info: DiagnosticsInfo,
+) {
     let window = Window::builder().title("lightdm-contest-greeter").build();
 
-    size_to_first_monitor(&window);
     window.set_decorated(false);
 
-    let background = Background::new();
-    if let Some(source) = conf.background_source.clone() {
-        background.set_image(&source)
-    }
-    let background_overlay = background.get_overlay();
-    window.set_child(Some(background_overlay));
+    let status_bus = bus.clone();
+    status_bus.send_to(
+        CoreName::Status,
+        StatusMessage::SetCountdownEndtime(
+            conf.countdown_end_time.map(|dt| dt.with_timezone(&Local)),
+        ),
+    );
+    let background = Background::new(&conf, {
+        let status_bus = status_bus.clone();
+        move |status| status_bus.send_to(CoreName::Status, StatusMessage::SetWallpaper(status))
+    });
+    let background_overlay = background.get_overlay().clone();
+    window.set_child(Some(&background_overlay));
+    let monitors = Monitors::new(&conf, &window, background);
+
+    let announcer = Announcer::new();
+    let accessibility = Accessibility::new(&conf.accessibility, &window, announcer.clone());
+    let countdown = CountDown::new(conf.clone(), bus.clone(), announcer.clone());
+
+    let phase_banner = PhaseBanner::new();
+    let message_overlay = MessageOverlay::new();
+    let identity = IdentityPanel::new(&conf.identity);
+    let clock = Clock::new(
+        &conf.clock,
+        conf.countdown_end_time.map(|dt| dt.with_timezone(&Local)),
+    );
+    let staff_attention = StaffAttention::new();
 
-    let countdown = CountDown::new(conf.clone(), bus.clone());
-    let countdown_overlay = countdown.widget();
+    let diagnostics = DiagnosticsPanel::new(diagnostics_info);
+    let login_ui = build_login_ui(bus.clone());
+    let theme = Theme::load(&conf);
 
-    let login_ui = build_login_ui(bus);
+    let layout = &conf.layout;
+    layout::add_extras(&background_overlay, layout);
+    layout::place(&background_overlay, countdown.widget(), &layout.countdown);
+    layout::place(
+        &background_overlay,
+        countdown.connection_dot(),
+        &layout.status_dot,
+    );
+    layout::place(
+        &background_overlay,
+        phase_banner.widget(),
+        &layout.phase_banner,
+    );
+    layout::place(&background_overlay, identity.widget(), &layout.identity);
+    layout::place(&background_overlay, clock.widget(), &layout.clock);
+    layout::place(
+        &background_overlay,
+        message_overlay.widget(),
+        &layout.message,
+    );
+    background_overlay.add_overlay(staff_attention.widget());
+    layout::place(&background_overlay, login_ui.widget(), &layout.login);
+    background_overlay.add_overlay(diagnostics.widget());
+    background_overlay.add_overlay(announcer.widget());
 
-    background_overlay.add_overlay(countdown_overlay);
-    background_overlay.add_overlay(login_ui.widget());
-    let login_ui_clone = login_ui.clone();
-    register_chain_listener(&window, conf.chain.chars().collect(), {
-        let login_ui = login_ui_widget_closure(login_ui_clone);
-        move || login_ui()
+    let a11y = &conf.accessibility;
+    let chains = std::iter::once((conf.chain.as_str(), ChainAction::ToggleLogin))
+        .chain([
+            (a11y.high_contrast_keys.as_str(), ChainAction::HighContrast),
+            (a11y.large_text_keys.as_str(), ChainAction::LargeText),
+        ])
+        .filter(|(keys, _)| !keys.is_empty())
+        .chain(conf.chains.iter().map(|c| (c.keys.as_str(), c.action)))
+        .filter_map(|(keys, action)| match Chain::parse(keys, action) {
+            Ok(chain) => Some(chain),
+            Err(e) => {
+                warn!("[UI] invalid chain {keys}: {e}");
+                None
+            }
+        })
+        .collect();
+    let timeout = (conf.chain_timeout > 0).then(|| Duration::from_millis(conf.chain_timeout));
+    register_chain_listener(&window, chains, timeout, {
+        let login_ui = login_ui.clone();
+        let diagnostics = diagnostics.clone();
+        let reboot_requested = Cell::new(None);
+        move |action| {
+            run_chain_action(
+                action,
+                &login_ui,
+                &diagnostics,
+                &accessibility,
+                &reboot_requested,
+                &bus,
+            )
+        }
     });
 
+    let widgets = Widgets {
+        monitors,
+        login_ui,
+        announcer,
+        countdown,
+        clock,
+        phase_banner,
+        staff_attention,
+        message_overlay,
+        identity,
+        diagnostics,
+        status_bus,
+        _theme: theme,
+    };
     idle_add_local(move || {
         while let Ok(msg) = rx.try_recv() {
-            match msg {
-                UiMessage::SetWallpaper(path_option) => match path_option {
-                    Some(path) => {
-                        background.set_image(&path.to_string());
-                    }
-                    None => {
-                        background.set_empty();
-                    }
-                },
-                UiMessage::SetError(error) => {
-                    login_ui.set_error_text(&error.to_string());
-                }
-                UiMessage::SetCountdownEndtime { end_time } => {
-                    countdown.update_endtime(end_time);
-                }
-            }
+            widgets.handle(msg);
         }
         ControlFlow::Continue
     });
 
     window.present();
-    window.fullscreen();
+}
+
+/// Everything the UI messages act on, owned by the message loop for as long as the greeter runs.
+struct Widgets<S: SystemSender + Clone + 'static> {
+    monitors: Monitors,
+    login_ui: LoginUi,
+    announcer: Announcer,
+    countdown: CountDown<S>,
+    clock: Clock,
+    phase_banner: PhaseBanner,
+    staff_attention: StaffAttention,
+    message_overlay: MessageOverlay,
+    identity: IdentityPanel,
+    diagnostics: DiagnosticsPanel,
+    status_bus: S,
+    /// Keeps watching the theme file for changes.
+    _theme: Option<Theme>,
+}
+
+impl<S: SystemSender + Clone + 'static> Widgets<S> {
+    fn handle(&self, msg: UiMessage) {
+        match msg {
+            UiMessage::SetWallpaper(path_option) => match path_option {
+                Some(path) => {
+                    self.monitors
+                        .set_wallpaper(Wallpaper::Image(path.to_string()));
+                }
+                None => {
+                    self.monitors.set_wallpaper(Wallpaper::Empty);
+                }
+            },
+            UiMessage::SetWallpaperPlaylist { sources, interval } => {
+                self.monitors
+                    .set_wallpaper(Wallpaper::Playlist(sources, interval));
+            }
+            UiMessage::SetError(error) => {
+                self.login_ui.set_error_text(&error.to_string());
+                self.announcer.announce(&error);
+            }
+            UiMessage::SetCountdownEndtime { end_time } => {
+                self.countdown.update_endtime(end_time);
+                self.clock.set_start_time(end_time);
+                // reported once the countdown actually follows it, a cleared end time too
+                self.status_bus.send_to(
+                    CoreName::Status,
+                    StatusMessage::SetCountdownEndtime(end_time),
+                );
+            }
+            UiMessage::SetContestPhase(phase) => {
+                self.status_bus
+                    .send_to(CoreName::Status, StatusMessage::SetContestPhase(phase));
+                self.phase_banner.set_phase(phase);
+                self.countdown.set_phase(phase);
+            }
+            UiMessage::ShowStaffAttention(reason) => {
+                self.staff_attention.show(&reason);
+                self.announcer
+                    .announce(&format!("{}. {reason}", i18n::tr("staff-attention-title")));
+            }
+            UiMessage::ShowMessage(announcement) => {
+                let text = [announcement.title.as_str(), announcement.body.as_str()]
+                    .into_iter()
+                    .filter(|part| !part.is_empty())
+                    .collect::<Vec<_>>()
+                    .join(". ");
+                self.announcer.announce(&text);
+                self.message_overlay.show(announcement);
+            }
+            UiMessage::ClearMessage => {
+                self.message_overlay.clear();
+            }
+            UiMessage::SetTeamName(name) => {
+                self.identity.set_team_name(&name);
+            }
+            UiMessage::SetContestInfo(info) => {
+                self.clock.set_contest_info(info);
+            }
+            UiMessage::SetKeyboardLayouts { layouts, active } => {
+                self.login_ui.set_layouts(layouts, active.as_deref());
+            }
+            UiMessage::ShowDiagnostics(visible) => {
+                show_diagnostics(&self.diagnostics, visible, &self.status_bus);
+            }
+        }
+    }
 }
 
 fn build_login_ui(bus: impl SystemSender) -> LoginUi {
-    let login_ui = LoginUi::new(Box::new(move |username, password| {
-        bus.send_to(
-            CoreName::Greeter,
-            GreeterMessage::LoginWithCreds(username, password),
-        );
-    }));
+    let layout_bus = bus.clone();
+    let login_ui = LoginUi::new(
+        Box::new(move |username, password| {
+            bus.send_to(
+                CoreName::Greeter,
+                GreeterMessage::LoginWithCreds(username, password),
+            );
+        }),
+        Box::new(move |layout| {
+            layout_bus.send_to(CoreName::Greeter, GreeterMessage::SetKeyboardLayout(layout));
+        }),
+    );
     login_ui.init();
     login_ui
 }
 
-fn login_ui_widget_closure(login_ui: LoginUi) -> impl Fn() {
-    move || {
-        login_ui.toggle();
+fn run_chain_action(
+    action: ChainAction,
+    login_ui: &LoginUi,
+    diagnostics: &DiagnosticsPanel,
+    accessibility: &Accessibility,
+    reboot_requested: &Cell<Option<Instant>>,
+    bus: &impl SystemSender,
+) {
+    info!("[UI] chain action {action:?}");
+    match action {
+        ChainAction::ToggleLogin => {
+            login_ui.toggle();
+            if login_ui.is_visible() {
+                bus.send_to(CoreName::Greeter, GreeterMessage::GetKeyboardLayouts);
+            }
+        }
+        ChainAction::Diagnostics => show_diagnostics(diagnostics, !diagnostics.is_visible(), bus),
+        ChainAction::AutoLogin => bus.send_to(
+            CoreName::Greeter,
+            GreeterMessage::Login(LoginSource::StaffChain),
+        ),
+        ChainAction::Reboot => {
+            let confirmed = reboot_requested
+                .take()
+                .is_some_and(|requested| requested.elapsed() < REBOOT_CONFIRM_TIMEOUT);
+            if confirmed {
+                bus.send_to(CoreName::UI, UiMessage::ClearMessage);
+                bus.send_to(CoreName::Greeter, GreeterMessage::Reboot);
+                return;
+            }
+            reboot_requested.set(Some(Instant::now()));
+            bus.send_to(
+                CoreName::UI,
+                UiMessage::ShowMessage(Announcement {
+                    title: i18n::tr("reboot-confirm-title"),
+                    body: i18n::tr_args(
+                        "reboot-confirm",
+                        &[("seconds", REBOOT_CONFIRM_TIMEOUT.as_secs().into())],
+                    ),
+                    severity: Severity::Warning,
+                    expires_at: Some(Local::now() + REBOOT_CONFIRM_TIMEOUT),
+                }),
+            );
+        }
+        ChainAction::HighContrast => accessibility.toggle_high_contrast(),
+        ChainAction::LargeText => accessibility.toggle_large_text(),
+        ChainAction::ReloadConfig => bus.send_to(CoreName::Greeter, GreeterMessage::ReloadConfig),
     }
 }
 
-// since we cannot the the primary monitor we size to the first one
-fn size_to_first_monitor(window: &Window) {
-    if let Some(display) = gdk::Display::default() {
-        let monitors = display.monitors();
-        if let Some(monitor) = monitors
-            .item(0)
-            .and_then(|m| m.downcast::<gdk::Monitor>().ok())
-        {
-            let geometry = monitor.geometry();
-            window.set_default_size(geometry.width(), geometry.height());
-        }
+/// Shows or hides the diagnostics panel, refreshing it every second while it is visible.
+fn show_diagnostics(diagnostics: &DiagnosticsPanel, visible: bool, bus: &impl SystemSender) {
+    if diagnostics.is_visible() == visible {
+        return;
+    }
+    diagnostics.set_visible(visible);
+    if !visible {
+        return;
     }
+
+    refresh_diagnostics(diagnostics, bus);
+    let diagnostics = diagnostics.clone();
+    let bus = bus.clone();
+    timeout_add_seconds_local(1, move || {
+        if !diagnostics.is_visible() {
+            return ControlFlow::Break;
+        }
+        refresh_diagnostics(&diagnostics, &bus);
+        ControlFlow::Continue
+    });
+}
+
+fn refresh_diagnostics(diagnostics: &DiagnosticsPanel, bus: &impl SystemSender) {
+    let status = query_status(bus);
+    let diagnostics = diagnostics.clone();
+    spawn_future_local(async move { diagnostics.refresh(status.await.as_ref()) });
 }
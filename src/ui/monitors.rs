@@ -0,0 +1,212 @@
+use std::{cell::RefCell, rc::Rc};
+
+use gtk4::{Window, gdk, glib::idle_add_local_once, prelude::*};
+use log::{debug, info};
+
+use crate::ui::background::Background;
+use crate::ui::config::UiConfig;
+
+/// Wallpaper requested for the main window, replayed on monitors that are plugged in later.
+#[derive(Clone)]
+pub enum Wallpaper {
+    Empty,
+    Image(String),
+    Playlist(Vec<String>, u64),
+}
+
+impl Wallpaper {
+    pub fn from_config(conf: &UiConfig) -> Self {
+        if !conf.background_playlist.is_empty() {
+            Wallpaper::Playlist(conf.background_playlist.clone(), conf.background_interval)
+        } else if let Some(source) = &conf.background_source {
+            Wallpaper::Image(source.clone())
+        } else {
+            Wallpaper::Empty
+        }
+    }
+
+    fn apply(&self, background: &Background) {
+        match self {
+            Wallpaper::Empty => background.set_empty(),
+            Wallpaper::Image(source) => background.set_image(source),
+            Wallpaper::Playlist(sources, interval) => {
+                background.set_playlist(sources.clone(), *interval)
+            }
+        }
+    }
+}
+
+struct SecondaryWindow {
+    monitor: gdk::Monitor,
+    window: Window,
+    background: Background,
+    // false when the monitor has its own wallpaper configured
+    follows_main: bool,
+}
+
+/// Places the main window on the configured monitor and covers the other monitors with
+/// wallpaper-only windows, following monitor hotplug.
+#[derive(Clone)]
+pub struct Monitors {
+    conf: Rc<UiConfig>,
+    main: Window,
+    background: Background,
+    secondary: Rc<RefCell<Vec<SecondaryWindow>>>,
+    wallpaper: Rc<RefCell<Wallpaper>>,
+}
+
+impl Monitors {
+    pub fn new(conf: &UiConfig, main: &Window, background: Background) -> Self {
+        let monitors = Self {
+            conf: Rc::new(conf.clone()),
+            main: main.clone(),
+            background,
+            secondary: Rc::new(RefCell::new(Vec::new())),
+            wallpaper: Rc::new(RefCell::new(Wallpaper::from_config(conf))),
+        };
+        monitors.wallpaper.borrow().apply(&monitors.background);
+        monitors.arrange();
+
+        if let Some(display) = gdk::Display::default() {
+            let handle = monitors.clone();
+            display.monitors().connect_items_changed(move |_, _, _, _| {
+                // the monitor list is updated in several steps, wait until it settles
+                let handle = handle.clone();
+                idle_add_local_once(move || handle.arrange());
+            });
+        }
+        monitors
+    }
+
+    pub fn set_wallpaper(&self, wallpaper: Wallpaper) {
+        wallpaper.apply(&self.background);
+        for secondary in self.secondary.borrow().iter() {
+            if secondary.follows_main {
+                wallpaper.apply(&secondary.background);
+            }
+        }
+        *self.wallpaper.borrow_mut() = wallpaper;
+    }
+
+    /// Moves the main window to the primary monitor and covers the others, keeping the windows
+    /// of monitors that are still there.
+    fn arrange(&self) {
+        let monitors = list_monitors();
+        let primary = self
+            .conf
+            .monitors
+            .primary
+            .as_deref()
+            .and_then(|name| {
+                let found = monitors
+                    .iter()
+                    .position(|(index, monitor)| matches(*index, monitor, name));
+                if found.is_none() {
+                    debug!("[UI] primary monitor {name} not found, using the first one");
+                }
+                found
+            })
+            .unwrap_or(0);
+
+        let Some((_, primary_monitor)) = monitors.get(primary) else {
+            return;
+        };
+        info!(
+            "[UI] {} monitor(s), main window on {}",
+            monitors.len(),
+            monitor_name(primary, primary_monitor)
+        );
+        place(&self.main, primary_monitor);
+
+        let covered = monitors
+            .iter()
+            .filter(|(index, _)| self.conf.monitors.all_monitors && *index != primary)
+            .collect::<Vec<_>>();
+        let mut secondary = self.secondary.borrow_mut();
+        // unplugged monitors, and the one the main window just moved to
+        secondary.retain(|window| {
+            let keep = covered
+                .iter()
+                .any(|(_, monitor)| *monitor == window.monitor);
+            if !keep {
+                window.background.set_empty();
+                window.window.destroy();
+            }
+            keep
+        });
+        for (index, monitor) in covered {
+            match secondary.iter().find(|window| window.monitor == *monitor) {
+                Some(existing) => place(&existing.window, monitor),
+                None => secondary.push(self.wallpaper_window(*index, monitor)),
+            }
+        }
+        if secondary.is_empty() {
+            return;
+        }
+        drop(secondary);
+
+        // keep keyboard focus on the login panel
+        self.main.present();
+    }
+
+    fn wallpaper_window(&self, index: usize, monitor: &gdk::Monitor) -> SecondaryWindow {
+        let window = Window::builder()
+            .title("lightdm-contest-greeter")
+            .decorated(false)
+            .css_classes(["secondary-monitor"])
+            .build();
+        let background = Background::new(&self.conf, |_| {});
+        window.set_child(Some(background.get_overlay()));
+
+        let own = self
+            .conf
+            .monitors
+            .wallpapers
+            .iter()
+            .find(|w| matches(index, monitor, &w.monitor));
+        match own {
+            Some(own) => {
+                background.set_playlist(vec![own.source.clone()], self.conf.background_interval)
+            }
+            None => self.wallpaper.borrow().apply(&background),
+        }
+
+        place(&window, monitor);
+        window.present();
+        SecondaryWindow {
+            monitor: monitor.clone(),
+            window,
+            background,
+            follows_main: own.is_none(),
+        }
+    }
+}
+
+fn list_monitors() -> Vec<(usize, gdk::Monitor)> {
+    let Some(display) = gdk::Display::default() else {
+        return Vec::new();
+    };
+    let model = display.monitors();
+    (0..model.n_items())
+        .filter_map(|i| model.item(i).and_downcast::<gdk::Monitor>())
+        .enumerate()
+        .collect()
+}
+
+/// Monitors are referred to by connector name, or by index when the backend doesn't report one.
+fn matches(index: usize, monitor: &gdk::Monitor, name: &str) -> bool {
+    monitor.connector().is_some_and(|c| c == name) || index.to_string() == name
+}
+
+fn monitor_name(index: usize, monitor: &gdk::Monitor) -> String {
+    monitor
+        .connector()
+        .map(|c| c.to_string())
+        .unwrap_or_else(|| index.to_string())
+}
+
+fn place(window: &Window, monitor: &gdk::Monitor) {
+    let geometry = monitor.geometry();
+    window.set_default_size(geometry.width(), geometry.height());
+    window.fullscreen_on_monitor(monitor);
+}
@@ -0,0 +1,82 @@
+use gtk4::{
+    AccessibleRole, Align, CssProvider, Label, STYLE_PROVIDER_PRIORITY_APPLICATION, gdk::Display,
+    prelude::*, style_context_add_provider_for_display,
+};
+use types::ContestPhase;
+
+use crate::i18n;
+
+pub struct PhaseBanner {
+    label: Label,
+}
+
+const PHASE_BANNER_CSS: &str = "
+    label.phase-banner {
+        font-size: 32px;
+        font-weight: bold;
+        color: white;
+        padding: 12px 32px;
+        margin-top: 20px;
+        border-radius: 12px;
+        background-color: rgba(30, 30, 30, 0.8);
+    }
+
+    label.phase-banner.frozen {
+        background-color: rgba(41, 128, 185, 0.85);
+    }
+
+    label.phase-banner.ended {
+        background-color: rgba(192, 57, 43, 0.85);
+    }
+";
+
+impl PhaseBanner {
+    pub fn new() -> Self {
+        let label = Label::builder()
+            .accessible_role(AccessibleRole::Status)
+            .visible(false)
+            .halign(Align::Center)
+            .valign(Align::Start)
+            .build();
+        label.add_css_class("phase-banner");
+
+        let css = CssProvider::new();
+        css.load_from_data(PHASE_BANNER_CSS);
+        if let Some(display) = Display::default() {
+            style_context_add_provider_for_display(
+                &display,
+                &css,
+                STYLE_PROVIDER_PRIORITY_APPLICATION,
+            );
+        }
+
+        Self { label }
+    }
+
+    pub fn widget(&self) -> &Label {
+        &self.label
+    }
+
+    pub fn set_phase(&self, phase: ContestPhase) {
+        self.label.remove_css_class("frozen");
+        self.label.remove_css_class("ended");
+
+        let text = match phase {
+            ContestPhase::BeforeStart => {
+                self.label.set_visible(false);
+                return;
+            }
+            ContestPhase::Running => "phase-running",
+            ContestPhase::Frozen => {
+                self.label.add_css_class("frozen");
+                "phase-frozen"
+            }
+            ContestPhase::Ended => {
+                self.label.add_css_class("ended");
+                "phase-ended"
+            }
+        };
+        self.label.set_text(&i18n::tr(text));
+        self.label.set_visible(true);
+    }
+}
@@ -0,0 +1,85 @@
+use gtk4::{
+    AccessibleRole, Align, Box as GTBox, CssProvider, Label, Orientation,
+    STYLE_PROVIDER_PRIORITY_APPLICATION, accessible::Property, gdk::Display, pango::WrapMode,
+    prelude::*, style_context_add_provider_for_display,
+};
+
+use crate::i18n;
+
+/// Full screen notice shown when the greeter gave up and needs a staff member.
+pub struct StaffAttention {
+    container: GTBox,
+    reason: Label,
+}
+
+const STAFF_ATTENTION_CSS: &str = "
+    .staff-attention {
+        background-color: rgba(120, 20, 20, 0.92);
+    }
+
+    .staff-attention label.title {
+        font-size: 64px;
+        font-weight: bold;
+        color: white;
+    }
+
+    .staff-attention label.reason {
+        font-size: 24px;
+        color: white;
+    }
+";
+
+impl StaffAttention {
+    pub fn new() -> Self {
+        let container = GTBox::builder()
+            .orientation(Orientation::Vertical)
+            .spacing(24)
+            .hexpand(true)
+            .vexpand(true)
+            .valign(Align::Fill)
+            .halign(Align::Fill)
+            .visible(false)
+            .accessible_role(AccessibleRole::Group)
+            .build();
+        container.update_property(&[Property::Label(&i18n::tr("staff-attention-title"))]);
+        container.add_css_class("staff-attention");
+
+        let title = Label::new(Some(&i18n::tr("staff-attention-title")));
+        title.add_css_class("title");
+        title.set_vexpand(true);
+        title.set_valign(Align::End);
+
+        let reason = Label::builder()
+            .wrap(true)
+            .wrap_mode(WrapMode::Word)
+            .max_width_chars(60)
+            .vexpand(true)
+            .valign(Align::Start)
+            .build();
+        reason.add_css_class("reason");
+
+        container.append(&title);
+        container.append(&reason);
+
+        let css = CssProvider::new();
+        css.load_from_data(STAFF_ATTENTION_CSS);
+        if let Some(display) = Display::default() {
+            style_context_add_provider_for_display(
+                &display,
+                &css,
+                STYLE_PROVIDER_PRIORITY_APPLICATION,
+            );
+        }
+
+        Self { container, reason }
+    }
+
+    pub fn widget(&self) -> &GTBox {
+        &self.container
+    }
+
+    pub fn show(&self, reason: &str) {
+        self.reason.set_text(reason);
+        self.container.set_visible(true);
+    }
+}
@@ -0,0 +1,99 @@
+use std::{
+    cell::Cell,
+    path::{Path, PathBuf},
+    rc::Rc,
+    time::Duration,
+};
+
+use gtk4::{
+    CssProvider, STYLE_PROVIDER_PRIORITY_USER,
+    gdk::Display,
+    gio::{self, Cancellable, FileMonitor, FileMonitorEvent, FileMonitorFlags},
+    glib::timeout_add_local_once,
+    prelude::*,
+    style_context_add_provider_for_display,
+};
+use log::{info, warn};
+
+use crate::ui::config::UiConfig;
+
+/// File loaded when the theme points to a directory.
+const THEME_FILE: &str = "theme.css";
+
+/// User supplied CSS, layered above the built-in styles of every widget.
+pub struct Theme {
+    _monitor: Option<FileMonitor>,
+}
+
+impl Theme {
+    pub fn load(conf: &UiConfig) -> Option<Self> {
+        let path = PathBuf::from(conf.theme.as_deref()?);
+        let css_path = if path.is_dir() {
+            path.join(THEME_FILE)
+        } else {
+            path
+        };
+        let file = gio::File::for_path(&css_path);
+
+        let provider = CssProvider::new();
+        provider.connect_parsing_error(|_, section, error| {
+            warn!("[UI] theme error at {}: {error}", section.to_str());
+        });
+        reload(&provider, &file);
+
+        if let Some(display) = Display::default() {
+            style_context_add_provider_for_display(
+                &display,
+                &provider,
+                STYLE_PROVIDER_PRIORITY_USER,
+            );
+        }
+
+        let monitor = if conf.theme_reload {
+            watch(&css_path, provider, file)
+        } else {
+            None
+        };
+        Some(Self { _monitor: monitor })
+    }
+}
+
+fn reload(provider: &CssProvider, file: &gio::File) {
+    if !file.query_exists(None::<&Cancellable>) {
+        warn!("[UI] theme file {} does not exist", file.parse_name());
+        return;
+    }
+    info!("[UI] loading theme {}", file.parse_name());
+    provider.load_from_file(file);
+}
+
+/// Watches the directory of the theme so changes to the CSS or its assets are picked up.
+fn watch(css_path: &Path, provider: CssProvider, file: gio::File) -> Option<FileMonitor> {
+    let dir = css_path.parent()?;
+    let monitor = match gio::File::for_path(dir)
+        .monitor_directory(FileMonitorFlags::WATCH_MOVES, None::<&Cancellable>)
+    {
+        Ok(monitor) => monitor,
+        Err(e) => {
+            warn!(
+                "[UI] failed to watch theme directory {}: {e}",
+                dir.display()
+            );
+            return None;
+        }
+    };
+
+    // editors write files in several steps, reload once they are done
+    let pending = Rc::new(Cell::new(false));
+    monitor.connect_changed(move |_, _, _, event| {
+        if event == FileMonitorEvent::AttributeChanged || pending.replace(true) {
+            return;
+        }
+        let (pending, provider, file) = (pending.clone(), provider.clone(), file.clone());
+        timeout_add_local_once(Duration::from_millis(250), move || {
+            pending.set(false);
+            reload(&provider, &file);
+        });
+    });
+    Some(monitor)
+}
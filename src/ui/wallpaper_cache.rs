@@ -0,0 +1,237 @@
+use std::{
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, SystemTime},
+};
+
+use anyhow::{Context, Result, anyhow};
+use log::{debug, warn};
+use reqwest::{
+    StatusCode,
+    blocking::{Client, Response},
+    header::{ETAG, IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::ui::image_check::{DecodedImage, ImageLimits, decode_bytes};
+
+/// On-disk cache of remote wallpapers so they are still shown when the network is down.
+#[derive(Clone)]
+pub struct WallpaperCache {
+    dir: PathBuf,
+    max_bytes: u64,
+}
+
+#[derive(Default, Deserialize, Serialize)]
+struct CacheMeta {
+    url: String,
+    etag: Option<String>,
+    last_modified: Option<String>,
+}
+
+impl WallpaperCache {
+    pub fn new(dir: impl Into<PathBuf>, max_megabytes: u64) -> Self {
+        Self {
+            dir: dir.into(),
+            max_bytes: max_megabytes * 1024 * 1024,
+        }
+    }
+
+    /// Downloads and decodes `url`, revalidating a cached copy with ETag/Last-Modified. The cached
+    /// copy is used when the server can't be reached or sends something that is not a valid image.
+    pub fn fetch(&self, client: &Client, url: &str, limits: ImageLimits) -> Result<DecodedImage> {
+        let (data_path, meta_path) = self.paths(url);
+        let meta = fs::read(&meta_path)
+            .ok()
+            .and_then(|raw| serde_json::from_slice::<CacheMeta>(&raw).ok())
+            // file names are hashes, a collision must not show the wallpaper of another url
+            .filter(|meta| meta.url == url);
+        let cached = meta.as_ref().and_then(|_| fs::read(&data_path).ok());
+        let meta = meta.unwrap_or_default();
+
+        let mut request = client.get(url);
+        if cached.is_some() {
+            if let Some(etag) = &meta.etag {
+                request = request.header(IF_NONE_MATCH, etag);
+            }
+            if let Some(last_modified) = &meta.last_modified {
+                request = request.header(IF_MODIFIED_SINCE, last_modified);
+            }
+        }
+
+        let response = match request.send().and_then(|r| r.error_for_status()) {
+            Ok(response) => response,
+            Err(e) => {
+                return match cached {
+                    Some(bytes) => {
+                        debug!("[UI] using cached wallpaper for {url}: {e}");
+                        decode_bytes(&bytes, limits)
+                    }
+                    None => Err(anyhow!(e).context("Failed to connect to the URL")),
+                };
+            }
+        };
+
+        if response.status() == StatusCode::NOT_MODIFIED
+            && let Some(bytes) = cached
+        {
+            debug!("[UI] cached wallpaper for {url} is up to date");
+            touch(&data_path);
+            return decode_bytes(&bytes, limits);
+        }
+
+        let meta = CacheMeta {
+            url: url.to_string(),
+            etag: header_value(&response, ETAG),
+            last_modified: header_value(&response, LAST_MODIFIED),
+        };
+        let (bytes, image) = match read_limited(response, limits.max_bytes)
+            .and_then(|bytes| decode_bytes(&bytes, limits).map(|image| (bytes, image)))
+        {
+            Ok(downloaded) => downloaded,
+            Err(e) => {
+                return match cached {
+                    Some(bytes) => {
+                        warn!("[UI] using cached wallpaper for {url}: {e:#}");
+                        decode_bytes(&bytes, limits)
+                    }
+                    None => Err(e),
+                };
+            }
+        };
+
+        if let Err(e) = self.store(&data_path, &meta_path, &bytes, &meta) {
+            warn!("[UI] failed to cache wallpaper {url}: {e:#}");
+        }
+        Ok(image)
+    }
+
+    fn store(
+        &self,
+        data_path: &Path,
+        meta_path: &Path,
+        bytes: &[u8],
+        meta: &CacheMeta,
+    ) -> Result<()> {
+        if bytes.len() as u64 > self.max_bytes {
+            return Err(anyhow!("image is larger than the cache"));
+        }
+        fs::create_dir_all(&self.dir).context(format!("creating {}", self.dir.display()))?;
+        // without metadata the image is not trusted, so an interrupted update is never paired
+        // with the validators of the previous image
+        let _ = fs::remove_file(meta_path);
+        write_atomic(data_path, bytes)?;
+        write_atomic(meta_path, &serde_json::to_vec(meta)?)?;
+        self.evict();
+        Ok(())
+    }
+
+    /// Removes the least recently used images until the cache fits in `max_bytes`.
+    fn evict(&self) {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return;
+        };
+        let mut images = entries
+            .filter_map(|entry| entry.ok())
+            .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "img"))
+            .filter_map(|entry| {
+                let meta = entry.metadata().ok()?;
+                let used = meta.modified().unwrap_or(SystemTime::UNIX_EPOCH);
+                Some((entry.path(), meta.len(), used))
+            })
+            .collect::<Vec<_>>();
+        images.sort_by_key(|(_, _, used)| *used);
+
+        let mut total: u64 = images.iter().map(|(_, size, _)| size).sum();
+        for (path, size, _) in images {
+            if total <= self.max_bytes {
+                break;
+            }
+            debug!("[UI] evicting cached wallpaper {}", path.display());
+            let _ = fs::remove_file(&path);
+            let _ = fs::remove_file(path.with_extension("json"));
+            total -= size;
+        }
+    }
+
+    fn paths(&self, url: &str) -> (PathBuf, PathBuf) {
+        let key = format!("{:016x}", fnv1a(url.as_bytes()));
+        (
+            self.dir.join(format!("{key}.img")),
+            self.dir.join(format!("{key}.json")),
+        )
+    }
+}
+
+fn header_value(response: &Response, name: reqwest::header::HeaderName) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|v| v.to_str().ok())
+        .map(str::to_string)
+}
+
+/// Writes through a temporary file, other greeter windows may read the same entry meanwhile.
+fn write_atomic(path: &Path, bytes: &[u8]) -> Result<()> {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let tmp = path.with_extension(format!(
+        "{}.{}.tmp",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    fs::write(&tmp, bytes).context(format!("writing {}", tmp.display()))?;
+    fs::rename(&tmp, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        anyhow!(e).context(format!("renaming {} to {}", tmp.display(), path.display()))
+    })
+}
+
+/// Marks a cache entry as recently used for eviction.
+fn touch(path: &Path) {
+    if let Ok(file) = fs::File::options().append(true).open(path) {
+        let _ = file.set_modified(SystemTime::now());
+    }
+}
+
+/// Stable hash for cache file names, unlike `DefaultHasher` it doesn't change between releases.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, b| {
+        (hash ^ *b as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// Client used for wallpaper downloads; the connect timeout keeps an offline venue from
+/// delaying the fallback to the cache, `timeout` bounds the whole download.
+pub fn http_client(timeout: Duration) -> Result<Client> {
+    Client::builder()
+        .connect_timeout(Duration::from_secs(5))
+        .timeout(timeout)
+        .build()
+        .context("building http client")
+}
+
+/// Reads the response body, giving up as soon as it exceeds `max_bytes` instead of buffering
+/// an arbitrarily large download.
+pub fn read_limited(response: Response, max_bytes: u64) -> Result<Vec<u8>> {
+    let too_large = || anyhow!("download is too large (max {} MB)", max_bytes / 1024 / 1024);
+    if response.content_length().is_some_and(|len| len > max_bytes) {
+        return Err(too_large());
+    }
+
+    let mut bytes = Vec::new();
+    response
+        .take(max_bytes + 1)
+        .read_to_end(&mut bytes)
+        .map_err(
+            |e| match e.get_ref().and_then(|e| e.downcast_ref::<reqwest::Error>()) {
+                Some(e) if e.is_timeout() => anyhow!("download timed out"),
+                _ => anyhow!(e).context("Failed to download image bytes"),
+            },
+        )?;
+    if bytes.len() as u64 > max_bytes {
+        return Err(too_large());
+    }
+    Ok(bytes)
+}
@@ -7,18 +7,63 @@ use serde_json::Value;
 
 const ROOT_ORDER: &[&str] = &[
     "log_level",
+    "log_output",
+    "language",
     "enable_dbus",
+    "dbus_allowed_users",
+    "dbus_allowed_groups",
+    "dbus_polkit",
+    "remote_listen",
+    "remote_token_file",
+    "remote_tls_cert",
+    "remote_tls_key",
+    "heartbeat_url",
+    "heartbeat_interval",
+    "metrics_listen",
     "chain",
+    "chains",
+    "chain_timeout",
     "background_source",
+    "background_playlist",
+    "background_interval",
+    "background_crossfade",
+    "background_fit",
+    "background_color",
+    "background_blur",
+    "background_dim",
+    "background_cache",
+    "background_cache_dir",
+    "background_cache_size",
+    "background_max_size",
+    "background_max_dimension",
+    "background_timeout",
     "countdown_end_time",
     "countdown_from",
     "countdown_end_login",
     "countdown_label_color",
+    "theme",
+    "theme_reload",
+    "identity",
+    "clock",
+    "monitors",
+    "layout",
+    "accessibility",
     "session",
     "username",
     "password",
+    "autologin_limit",
+    "autologin_window",
+    "autologin_record",
+    "audit_log",
+    "login_free_attempts",
+    "login_global_free_attempts",
+    "login_delay",
+    "login_max_delay",
+    "keyboard_layout",
     "interval",
     "url",
+    "clarifications_url",
+    "teams_url",
 ];
 
 struct Row {
@@ -151,6 +196,10 @@ fn as_object<'a>(
                 let name = reference.rsplit('/').next()?;
                 let referenced = definitions.get(name)?;
                 as_object(referenced, definitions)
+            } else if let Some([single]) = obj.subschemas.as_ref().and_then(|s| s.all_of.as_deref())
+            {
+                // fields with a default are wrapped as `allOf: [{ $ref }]`
+                as_object(single, definitions)
             } else {
                 Some(obj)
             }
@@ -160,6 +209,15 @@ fn as_object<'a>(
 }
 
 fn description(schema: &Schema, definitions: &Map<String, Schema>) -> String {
+    // prefer the field's own doc comment over the one of the referenced type
+    if let Schema::Object(SchemaObject {
+        metadata: Some(meta),
+        ..
+    }) = schema
+        && let Some(description) = &meta.description
+    {
+        return description.clone();
+    }
     as_object(schema, definitions)
         .and_then(|obj| obj.metadata.as_ref())
         .and_then(|meta| meta.description.clone())
@@ -177,6 +235,22 @@ fn schema_type(schema: &Schema, definitions: &Map<String, Schema>) -> String {
         if let Some(any) = &subschemas.any_of {
             return join_types(any.iter().map(|s| schema_type(s, definitions)).collect());
         }
+        // enums with documented variants are rendered as one schema per variant
+        if let Some(one) = &subschemas.one_of {
+            return join_types(one.iter().map(|s| schema_type(s, definitions)).collect());
+        }
+    }
+
+    if let Some(values) = &obj.enum_values {
+        return values
+            .iter()
+            .map(|v| {
+                v.as_str()
+                    .map(|s| format!("`{s}`"))
+                    .unwrap_or(v.to_string())
+            })
+            .collect::<Vec<_>>()
+            .join(" | ");
     }
 
     match &obj.instance_type {
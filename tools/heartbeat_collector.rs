@@ -0,0 +1,45 @@
+//! Minimal heartbeat collector for trying out `heartbeat_url` locally.
+//!
+//! Usage: `cargo run --bin heartbeat-collector [listen address]` (defaults to `127.0.0.1:8422`),
+//! then set `heartbeat_url = "http://127.0.0.1:8422/heartbeat"`. Every heartbeat is printed, and
+//! `GET /` returns the latest heartbeat of each host.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use anyhow::Result;
+use axum::{Json, Router, extract::State, http::StatusCode, routing::get, routing::post};
+use serde_json::Value;
+
+type Hosts = Arc<Mutex<BTreeMap<String, Value>>>;
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let address = std::env::args()
+        .nth(1)
+        .unwrap_or_else(|| "127.0.0.1:8422".into());
+    let hosts = Hosts::default();
+
+    let app = Router::new()
+        .route("/", get(overview))
+        .route("/heartbeat", post(heartbeat))
+        .with_state(hosts);
+
+    let listener = tokio::net::TcpListener::bind(&address).await?;
+    println!("listening on {address}");
+    axum::serve(listener, app).await?;
+    Ok(())
+}
+
+async fn heartbeat(State(hosts): State<Hosts>, Json(heartbeat): Json<Value>) -> StatusCode {
+    println!("{heartbeat}");
+    let host = heartbeat["host"].as_str().unwrap_or("unknown").to_string();
+    hosts.lock().unwrap().insert(host, heartbeat);
+    StatusCode::NO_CONTENT
+}
+
+async fn overview(State(hosts): State<Hosts>) -> Json<BTreeMap<String, Value>> {
+    Json(hosts.lock().unwrap().clone())
+}
@@ -7,6 +7,8 @@ use tokio::sync::mpsc::Sender;
 pub enum CoreName {
     Greeter,
     UI,
+    Status,
+    DBus,
 }
 
 pub enum SystemMsg {
@@ -21,17 +23,21 @@ pub enum SystemMsg {
 }
 
 pub trait ServiceChannel: Send {
-    fn send_any(&self, msg: Box<dyn Any + Send>);
+    /// Returns whether the message was delivered.
+    fn send_any(&self, msg: Box<dyn Any + Send>) -> bool;
 }
 
 impl<T: 'static + Send> ServiceChannel for Sender<T> {
-    fn send_any(&self, msg: Box<dyn Any + Send>) {
+    fn send_any(&self, msg: Box<dyn Any + Send>) -> bool {
         if let Ok(typed_msg) = msg.downcast::<T>() {
             if let Err(e) = self.try_send(*typed_msg) {
-                warn!("[Bus] failed to dispatch message: {}", e)
+                warn!("[Bus] failed to dispatch message: {}", e);
+                return false;
             }
+            true
         } else {
-            debug!("[Bus] wrong message type sent to channel")
+            debug!("[Bus] wrong message type sent to channel");
+            false
         }
     }
 }
@@ -1,5 +1,12 @@
 mod bus;
 mod messages;
+mod status;
 
 pub use bus::{CoreName, ServiceChannel, SystemBus, SystemHandle, SystemMsg, SystemSender};
-pub use messages::{GreeterMessage, UiMessage};
+pub use messages::{
+    Announcement, ContestInfo, ContestPhase, DbusMessage, GreeterMessage, KeyboardLayout,
+    LoginSource, Severity, UiMessage,
+};
+pub use status::{
+    AuthState, DbusStatus, ErrorReport, GreeterStatus, SourceHealth, StatusMessage, WallpaperStatus,
+};
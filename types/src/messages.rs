@@ -1,13 +1,141 @@
-use chrono::{DateTime, Local};
+use std::{fmt, str::FromStr};
+
+use chrono::{DateTime, Local, TimeDelta};
+
+/// Phase of the contest as reported by the contest API.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ContestPhase {
+    BeforeStart,
+    Running,
+    Frozen,
+    Ended,
+}
+
+impl fmt::Display for ContestPhase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ContestPhase::BeforeStart => "before_start",
+            ContestPhase::Running => "running",
+            ContestPhase::Frozen => "frozen",
+            ContestPhase::Ended => "ended",
+        };
+        write!(f, "{name}")
+    }
+}
+
+/// Contest details used for the schedule shown on the greeter.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContestInfo {
+    pub name: Option<String>,
+    pub start_time: DateTime<Local>,
+    pub duration: Option<TimeDelta>,
+}
+
+/// A keyboard layout that can be selected in the login panel.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyboardLayout {
+    /// LightDM layout name, e.g. `us` or `de\tnodeadkeys`.
+    pub name: String,
+    /// Short label such as `us`.
+    pub short: String,
+    /// Human readable name such as `English (US)`.
+    pub description: String,
+}
+
+/// What triggered an automatic login, recorded in the audit log.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LoginSource {
+    Countdown,
+    ContestApi,
+    DBus,
+    StaffChain,
+    Remote,
+}
+
+impl fmt::Display for LoginSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            LoginSource::Countdown => "countdown",
+            LoginSource::ContestApi => "contest_api",
+            LoginSource::DBus => "dbus",
+            LoginSource::StaffChain => "staff_chain",
+            LoginSource::Remote => "remote",
+        };
+        write!(f, "{name}")
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+impl FromStr for Severity {
+    type Err = String;
+
+    /// Parses `info`, `warning` or `critical`, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "info" => Ok(Severity::Info),
+            "warning" => Ok(Severity::Warning),
+            "critical" => Ok(Severity::Critical),
+            other => Err(format!("Unknown severity {other}")),
+        }
+    }
+}
+
+/// A message shown to contestants on top of the greeter.
+#[derive(Clone, Debug)]
+pub struct Announcement {
+    pub title: String,
+    pub body: String,
+    pub severity: Severity,
+    pub expires_at: Option<DateTime<Local>>,
+}
 
 pub enum UiMessage {
     SetWallpaper(Option<String>),
+    SetWallpaperPlaylist {
+        sources: Vec<String>,
+        interval: u64,
+    },
     SetError(String),
-    SetCountdownEndtime { end_time: Option<DateTime<Local>> },
+    SetCountdownEndtime {
+        end_time: Option<DateTime<Local>>,
+    },
+    SetContestPhase(ContestPhase),
+    ShowStaffAttention(String),
+    ShowMessage(Announcement),
+    ClearMessage,
+    SetTeamName(String),
+    SetContestInfo(ContestInfo),
+    ShowDiagnostics(bool),
+    SetKeyboardLayouts {
+        layouts: Vec<KeyboardLayout>,
+        active: Option<String>,
+    },
+}
+
+pub enum DbusMessage {
+    /// The wallpaper status changed, D-Bus clients watching it are notified.
+    WallpaperStatusChanged,
 }
 
 pub enum GreeterMessage {
     LoginWithCreds(String, String),
-    Login(),
+    Login(LoginSource),
     StartSession(Option<String>),
+    SetContestPhase(ContestPhase),
+    Reboot,
+    AuthenticationComplete(bool),
+    SetKeyboardLayout(String),
+    GetKeyboardLayouts,
+    /// Sent by the UI once it receives messages, so state from before it started is sent again.
+    UiStarted,
+    /// Re-reads the configuration file and applies the settings that can change while running.
+    ReloadConfig,
+    /// A deferred countdown login waited `phase_wait` seconds without a contest phase.
+    PhaseWaitExpired,
 }
@@ -0,0 +1,131 @@
+use std::fmt;
+
+use chrono::{DateTime, Local};
+use tokio::sync::oneshot;
+
+use crate::ContestPhase;
+
+/// Outcome of the last attempt to show a wallpaper.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum WallpaperStatus {
+    #[default]
+    Empty,
+    Loading(String),
+    Loaded(String),
+    Invalid {
+        source: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for WallpaperStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WallpaperStatus::Empty => write!(f, "empty"),
+            WallpaperStatus::Loading(source) => write!(f, "loading: {source}"),
+            WallpaperStatus::Loaded(source) => write!(f, "loaded: {source}"),
+            WallpaperStatus::Invalid { source, reason } => {
+                write!(f, "invalid: {source}: {reason}")
+            }
+        }
+    }
+}
+
+/// Whether the last request to an external source succeeded.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum SourceHealth {
+    #[default]
+    Unknown,
+    Healthy,
+    Failing(String),
+}
+
+impl fmt::Display for SourceHealth {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SourceHealth::Unknown => write!(f, "unknown"),
+            SourceHealth::Healthy => write!(f, "healthy"),
+            SourceHealth::Failing(reason) => write!(f, "failing: {reason}"),
+        }
+    }
+}
+
+/// Progress of the login handled by the greeter.
+#[derive(Clone, Debug, Default)]
+pub enum AuthState {
+    #[default]
+    Idle,
+    Authenticating(String),
+    Failed(String),
+    SessionStarted(String),
+    /// Automatic login stopped after too many attempts.
+    Halted,
+    /// A countdown login waits for the contest API to report the contest phase.
+    WaitingForPhase,
+}
+
+impl fmt::Display for AuthState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AuthState::Idle => write!(f, "idle"),
+            AuthState::Authenticating(user) => write!(f, "authenticating: {user}"),
+            AuthState::Failed(user) => write!(f, "failed: {user}"),
+            AuthState::SessionStarted(user) => write!(f, "session_started: {user}"),
+            AuthState::Halted => write!(f, "halted"),
+            AuthState::WaitingForPhase => write!(f, "waiting_for_phase"),
+        }
+    }
+}
+
+/// Whether the D-Bus service owns its bus name.
+#[derive(Clone, Debug, Default)]
+pub enum DbusStatus {
+    #[default]
+    Disabled,
+    Starting,
+    Registered(String),
+    Failed(String),
+}
+
+impl fmt::Display for DbusStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DbusStatus::Disabled => write!(f, "disabled"),
+            DbusStatus::Starting => write!(f, "starting"),
+            DbusStatus::Registered(name) => write!(f, "registered: {name}"),
+            DbusStatus::Failed(reason) => write!(f, "failed: {reason}"),
+        }
+    }
+}
+
+/// The most recent error reported by any core.
+#[derive(Clone, Debug)]
+pub struct ErrorReport {
+    pub time: DateTime<Local>,
+    pub message: String,
+}
+
+/// Runtime state of the greeter, collected from the other cores.
+#[derive(Clone, Debug, Default)]
+pub struct GreeterStatus {
+    pub wallpaper: WallpaperStatus,
+    pub countdown_end: Option<DateTime<Local>>,
+    pub contest_phase: Option<ContestPhase>,
+    pub contest_api: SourceHealth,
+    pub contest_api_polled: Option<DateTime<Local>>,
+    pub dbus: DbusStatus,
+    pub auth: AuthState,
+    pub last_error: Option<ErrorReport>,
+}
+
+pub enum StatusMessage {
+    SetWallpaper(WallpaperStatus),
+    SetCountdownEndtime(Option<DateTime<Local>>),
+    SetContestPhase(ContestPhase),
+    /// Result of a contest API poll.
+    SetContestApi(SourceHealth),
+    SetDbus(DbusStatus),
+    SetAuthState(AuthState),
+    ReportError(String),
+    Get(oneshot::Sender<GreeterStatus>),
+}